@@ -0,0 +1,171 @@
+use crate::{
+    err::{Err, Result},
+    options::WarningKind,
+};
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io, path,
+};
+
+const FILE_NAME: &str = ".git-project-baseline.json";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Warnings that `check` has been told to stop reporting.
+///
+/// Recorded warnings are matched by their message, so a warning that changes,
+/// such as a branch getting further ahead of its upstream, is reported again.
+/// Snoozed kinds are not reported for a repository until their date passes.
+#[derive(Default)]
+pub struct Baseline {
+    warnings: BTreeMap<String, BTreeSet<String>>,
+    snoozes: Vec<Snooze>,
+}
+
+pub struct Snooze {
+    /// Path of the repository relative to the base directory
+    pub repo: String,
+    pub kind: WarningKind,
+    /// Last day the kind is not reported
+    pub until: NaiveDate,
+}
+
+impl Baseline {
+    pub fn default_path(base_dir: &path::Path) -> path::PathBuf {
+        base_dir.join(FILE_NAME)
+    }
+
+    /// Read a baseline, which is empty if the file does not exist
+    pub fn load(path: &path::Path) -> Result<Baseline> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Baseline::default()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let value = serde_json::from_str(&text)?;
+
+        Baseline::from_json(&value).map_err(|message| Err::InvalidBaseline {
+            path: path.to_owned(),
+            message,
+        })
+    }
+
+    pub fn save(&self, path: &path::Path) -> Result<()> {
+        let text = serde_json::to_string_pretty(&self.to_json())?;
+        fs::write(path, text + "\n")?;
+
+        Ok(())
+    }
+
+    pub fn from_json(value: &Value) -> std::result::Result<Baseline, String> {
+        let mut baseline = Baseline::default();
+
+        if let Some(warnings) = value.get("warnings") {
+            let warnings = warnings.as_object().ok_or("warnings is not an object")?;
+
+            for (repo, messages) in warnings {
+                let messages = messages
+                    .as_array()
+                    .ok_or_else(|| format!("warnings of {} are not a list", repo))?;
+
+                let messages = messages
+                    .iter()
+                    .map(|m| m.as_str().map(String::from))
+                    .collect::<Option<_>>()
+                    .ok_or_else(|| format!("warnings of {} are not all strings", repo))?;
+
+                baseline.warnings.insert(repo.clone(), messages);
+            }
+        }
+
+        if let Some(snoozes) = value.get("snoozed") {
+            let snoozes = snoozes.as_array().ok_or("snoozed is not a list")?;
+
+            for snooze in snoozes {
+                let field = |name| {
+                    snooze
+                        .get(name)
+                        .and_then(Value::as_str)
+                        .ok_or_else(|| format!("snooze is missing {}", name))
+                };
+
+                let until = field("until")?;
+
+                baseline.snoozes.push(Snooze {
+                    repo: field("repo")?.into(),
+                    kind: field("kind")?.parse()?,
+                    until: NaiveDate::parse_from_str(until, DATE_FORMAT)
+                        .map_err(|_| format!("Invalid date {}", until))?,
+                });
+            }
+        }
+
+        Ok(baseline)
+    }
+
+    pub fn to_json(&self) -> Value {
+        let snoozes: Vec<Value> = self
+            .snoozes
+            .iter()
+            .map(|snooze| {
+                json!({
+                    "repo": snooze.repo,
+                    "kind": snooze.kind.to_string(),
+                    "until": snooze.until.format(DATE_FORMAT).to_string(),
+                })
+            })
+            .collect();
+
+        json!({
+            "warnings": self.warnings,
+            "snoozed": snoozes,
+        })
+    }
+
+    /// Replace the recorded warnings of the repositories that were checked,
+    /// keeping those of repositories that were not, such as ones outside the
+    /// selected groups. Repositories without warnings are forgotten.
+    pub fn update_warnings(&mut self, checked: BTreeMap<String, BTreeSet<String>>) {
+        for (repo, messages) in checked {
+            if messages.is_empty() {
+                self.warnings.remove(&repo);
+            } else {
+                self.warnings.insert(repo, messages);
+            }
+        }
+    }
+
+    /// Snooze a kind for a repository, replacing any earlier snooze of it
+    pub fn snooze(&mut self, snooze: Snooze) {
+        self.snoozes
+            .retain(|s| !(s.repo == snooze.repo && s.kind == snooze.kind));
+        self.snoozes.push(snooze);
+    }
+
+    /// Forget snoozes that ended before `today`
+    pub fn prune_snoozes(&mut self, today: NaiveDate) {
+        self.snoozes.retain(|s| s.until >= today);
+    }
+
+    /// Whether a warning about a repository should not be reported
+    pub fn suppresses(
+        &self,
+        repo: &str,
+        kind: WarningKind,
+        message: &str,
+        today: NaiveDate,
+    ) -> bool {
+        let recorded = self
+            .warnings
+            .get(repo)
+            .is_some_and(|messages| messages.contains(message));
+
+        recorded
+            || self
+                .snoozes
+                .iter()
+                .any(|s| s.repo == repo && s.kind == kind && s.until >= today)
+    }
+}
@@ -1,39 +1,115 @@
 use crate::{
-    err::Result,
+    baseline::{Baseline, Snooze},
+    commands::duplicates,
+    config::Config,
+    err::{Err, Result},
     explore,
-    options::CheckOptions,
+    options::{CheckOptions, Protocol, Severity, WarningKind},
+    policy::{self, WarningPolicy},
+    remotes::RemotePreference,
     util::{self, PathRelativizeExtension},
 };
 use rayon::prelude::*;
-use std::{collections::HashMap, fmt, iter, path};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt, iter, path, process,
+};
+
+/// How thoroughly `work_warnings` compares a repository with its remotes
+#[derive(Default)]
+pub struct WorkOptions {
+    /// Compare local branches that have no upstream with the branch of the
+    /// same name on every remote
+    pub match_names: bool,
+    /// Ask each remote for its tags with `git ls-remote` to find tags that
+    /// were not pushed, instead of looking for tags that no remote-tracking
+    /// branch contains
+    pub fetch: bool,
+    /// Do not look for tags that were not pushed
+    pub skip_tags: bool,
+}
 
 struct Repository {
     path: String,
-    warnings: Vec<Warning>,
+    warnings: Vec<(Severity, Warning)>,
+    /// Warnings that `--fix` resolved
+    fixed: Vec<Warning>,
 }
 
-enum Warning {
+/// Identity settings that can be required per host, as the git config key
+/// and the `gitproject` key holding its expected value
+const IDENTITY_KEYS: &[(&str, &str)] = &[
+    ("user.name", "name"),
+    ("user.email", "email"),
+    ("user.signingkey", "signingkey"),
+    ("commit.gpgsign", "gpgsign"),
+];
+
+pub enum Warning {
     NoRemotes,
     DirtyWorkingDir,
     LocalCommitsNotOnRemote {
-        remote: String,
         branch: String,
+        upstream: String,
         ahead_by: usize,
     },
     LocalBranchNotOnRemote {
         remote: String,
         branch: String,
     },
-    LocalPathDifferentFromOrigin {
+    NoUpstream {
+        branch: String,
+    },
+    UpstreamGone {
+        branch: String,
+        upstream: String,
+    },
+    UnpushedTag {
+        tag: String,
+        target: String,
+    },
+    TagsUnknown {
+        remote: String,
+    },
+    Stashes {
+        count: usize,
+    },
+    OperationInProgress {
+        operation: &'static str,
+    },
+    DetachedHeadCommitsNotOnBranch {
+        commits: usize,
+    },
+    LocalPathDifferentFromRemote {
         local_path: path::PathBuf,
         expected_path: path::PathBuf,
-        origin: String,
+        remote: String,
+        url: String,
+    },
+    DuplicateClone {
+        others: Vec<path::PathBuf>,
+    },
+    WrongProtocol {
+        remote: String,
+        url: String,
+        expected: Protocol,
+    },
+    CredentialsInUrl {
+        remote: String,
+        url: String,
+    },
+    WrongIdentity {
+        key: &'static str,
+        actual: Option<String>,
+        expected: String,
+        host: String,
     },
 }
 
 #[derive(Default)]
 struct Statistics {
     warnings: usize,
+    errors: usize,
     total_repos: usize,
     repos_with_warnings: usize,
     repos_no_warnings: usize,
@@ -43,44 +119,118 @@ impl fmt::Display for Repository {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "{}", self.path)?;
 
-        for warning in &self.warnings {
-            writeln!(f, "  - {}", warning)?;
+        for (severity, warning) in &self.warnings {
+            writeln!(f, "  - {}: {}", severity, warning)?;
+        }
+
+        for warning in &self.fixed {
+            writeln!(f, "  - fixed: {}", warning)?;
         }
 
         Ok(())
     }
 }
 
+impl Warning {
+    pub fn kind(&self) -> WarningKind {
+        match self {
+            Warning::NoRemotes => WarningKind::NoRemotes,
+            Warning::DirtyWorkingDir => WarningKind::Dirty,
+            Warning::LocalCommitsNotOnRemote { .. } => WarningKind::Ahead,
+            Warning::LocalBranchNotOnRemote { .. } => WarningKind::NotOnRemote,
+            Warning::NoUpstream { .. } => WarningKind::NoUpstream,
+            Warning::UpstreamGone { .. } => WarningKind::UpstreamGone,
+            Warning::UnpushedTag { .. } | Warning::TagsUnknown { .. } => WarningKind::UnpushedTag,
+            Warning::Stashes { .. } => WarningKind::Stash,
+            Warning::OperationInProgress { .. } => WarningKind::InProgress,
+            Warning::DetachedHeadCommitsNotOnBranch { .. } => WarningKind::Detached,
+            Warning::LocalPathDifferentFromRemote { .. } => WarningKind::Path,
+            Warning::DuplicateClone { .. } => WarningKind::Duplicate,
+            Warning::WrongProtocol { .. } => WarningKind::Protocol,
+            Warning::CredentialsInUrl { .. } => WarningKind::Credentials,
+            Warning::WrongIdentity { .. } => WarningKind::Identity,
+        }
+    }
+}
+
 impl fmt::Display for Warning {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Warning::NoRemotes => write!(f, "no remotes configured"),
             Warning::DirtyWorkingDir => write!(f, "working directory has changes not checked in"),
             Warning::LocalCommitsNotOnRemote {
-                remote,
                 branch,
+                upstream,
                 ahead_by,
             } => write!(
                 f,
-                "local branch {0} ahead of {1}/{0} by {2} commits",
-                branch, remote, ahead_by
+                "local branch {} ahead of {} by {} commits",
+                branch, upstream, ahead_by
             ),
             Warning::LocalBranchNotOnRemote { remote, branch } => write!(
                 f,
                 "local branch {} does not exist on remote {}",
                 branch, remote
             ),
-            Warning::LocalPathDifferentFromOrigin {
+            Warning::NoUpstream { branch } => {
+                write!(f, "local branch {} has no upstream configured", branch)
+            }
+            Warning::UpstreamGone { branch, upstream } => write!(
+                f,
+                "local branch {} tracks {}, which no longer exists",
+                branch, upstream
+            ),
+            Warning::UnpushedTag { tag, target } => {
+                write!(f, "tag {} at {} does not exist on any remote", tag, target)
+            }
+            Warning::TagsUnknown { remote } => {
+                write!(f, "could not list the tags of remote {}", remote)
+            }
+            Warning::Stashes { count } => write!(f, "stash has {} entries", count),
+            Warning::OperationInProgress { operation } => {
+                write!(f, "{} in progress", operation)
+            }
+            Warning::DetachedHeadCommitsNotOnBranch { commits } => {
+                write!(f, "detached HEAD has {} commits not on any branch", commits)
+            }
+            Warning::LocalPathDifferentFromRemote {
                 local_path,
-                origin,
                 expected_path,
+                remote,
+                url,
             } => write!(
                 f,
-                "should be at path {} based on origin url {}, but is at path {}",
+                "should be at path {} based on {} url {}, but is at path {}",
                 expected_path.display(),
-                origin,
+                remote,
+                url,
                 local_path.display(),
             ),
+            Warning::DuplicateClone { others } => {
+                let others: Vec<_> = others.iter().map(|p| p.display().to_string()).collect();
+                write!(f, "same remote is also cloned at {}", others.join(", "))
+            }
+            Warning::WrongProtocol {
+                remote,
+                url,
+                expected,
+            } => write!(f, "remote {} url {} should use {}", remote, url, expected),
+            Warning::CredentialsInUrl { remote, url } => {
+                write!(f, "remote {} url {} contains credentials", remote, url)
+            }
+            Warning::WrongIdentity {
+                key,
+                actual,
+                expected,
+                host,
+            } => write!(
+                f,
+                "{} is {}, but {} expects {}",
+                key,
+                actual.as_deref().unwrap_or("not set"),
+                host,
+                expected
+            ),
         }
     }
 }
@@ -91,8 +241,15 @@ impl Repository {
         let repos_with_warnings = if empty { 0 } else { 1 };
         let repos_no_warnings = if empty { 1 } else { 0 };
 
+        let errors = self
+            .warnings
+            .iter()
+            .filter(|(severity, _)| *severity == Severity::Error)
+            .count();
+
         Statistics {
             warnings: self.warnings.len(),
+            errors,
             total_repos: 1,
             repos_with_warnings,
             repos_no_warnings,
@@ -109,6 +266,7 @@ impl iter::Sum<Statistics> for Statistics {
 
         for s in iter {
             stats.warnings += s.warnings;
+            stats.errors += s.errors;
             stats.total_repos += s.total_repos;
             stats.repos_with_warnings += s.repos_with_warnings;
             stats.repos_no_warnings += s.repos_no_warnings;
@@ -122,6 +280,7 @@ impl fmt::Display for Statistics {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "---- Summary ---")?;
         writeln!(f, "Warnings: {}", self.warnings)?;
+        writeln!(f, "Errors: {}", self.errors)?;
         writeln!(f, "Scanned repositories: {}", self.total_repos)?;
         writeln!(
             f,
@@ -137,20 +296,98 @@ impl fmt::Display for Statistics {
 }
 
 pub fn run(check_opts: &CheckOptions) -> Result<()> {
-    let paths = explore::find_git_folders(&check_opts.base.base_dir, check_opts.list.deep_recurse)?;
+    let base_dir = &check_opts.base.base_dir;
+    let baseline_path = check_opts
+        .baseline
+        .clone()
+        .unwrap_or_else(|| Baseline::default_path(base_dir));
+
+    let mut baseline = if check_opts.no_baseline {
+        Baseline::default()
+    } else {
+        Baseline::load(&baseline_path)?
+    };
+
+    if !check_opts.snooze.is_empty() {
+        return snooze(check_opts, baseline, &baseline_path);
+    }
+
+    let paths = explore::find_repositories(base_dir, &check_opts.list)?;
+    let preference = RemotePreference::load(&check_opts.remote)?;
+    let policy = WarningPolicy::load(check_opts)?;
+    let config = Config::load()?;
+    let today = chrono::Local::now().date_naive();
+
+    let mut duplicates = HashMap::new();
+
+    if policy.severity(WarningKind::Duplicate).is_some() {
+        for group in duplicates::find_duplicates(&paths, base_dir, &preference) {
+            for copy in &group.copies {
+                let others = group
+                    .copies
+                    .iter()
+                    .filter(|other| *other != copy)
+                    .map(|other| other.normalize_relative_to(base_dir))
+                    .collect();
+
+                duplicates.insert(copy.clone(), others);
+            }
+        }
+    }
 
-    let stats: Statistics = paths
+    let repos = paths
         .par_iter()
-        .map(|dir| check_git_dir_entry(dir, &check_opts.base.base_dir))
+        .map(|dir| check_git_dir_entry(dir, check_opts, &preference, &policy, &duplicates, &config))
         .filter_map(|result| match result {
             Ok(x) => Some(x),
             Err(e) => {
                 eprintln!("Error received: {}", e);
                 None
             }
-        })
-        .map(|repo| {
-            if !repo.warnings.is_empty() {
+        });
+
+    if check_opts.update_baseline {
+        let repos: Vec<Repository> = repos.collect();
+
+        // Fixed warnings are gone, so they are listed but not recorded
+        for repo in repos.iter().filter(|repo| !repo.fixed.is_empty()) {
+            println!("{}", repo.path);
+
+            for warning in &repo.fixed {
+                println!("  - fixed: {}", warning);
+            }
+
+            println!();
+        }
+
+        let warnings: BTreeMap<String, BTreeSet<String>> = repos
+            .into_iter()
+            .map(|repo| {
+                let messages = repo.warnings.iter().map(|(_, w)| w.to_string()).collect();
+                (repo.path, messages)
+            })
+            .collect();
+
+        let count: usize = warnings.values().map(BTreeSet::len).sum();
+
+        baseline.update_warnings(warnings);
+        baseline.prune_snoozes(today);
+        baseline.save(&baseline_path)?;
+
+        println!("Recorded {} warnings in {}", count, baseline_path.display());
+
+        return Ok(());
+    }
+
+    let stats: Statistics = repos
+        .map(|mut repo| {
+            let path = &repo.path;
+
+            repo.warnings.retain(|(_, warning)| {
+                !baseline.suppresses(path, warning.kind(), &warning.to_string(), today)
+            });
+
+            if !repo.warnings.is_empty() || !repo.fixed.is_empty() {
                 println!("{}", repo);
             }
 
@@ -162,75 +399,529 @@ pub fn run(check_opts: &CheckOptions) -> Result<()> {
         println!("{}", stats);
     }
 
+    if stats.errors > 0 {
+        return Err(Err::ErrorWarnings(stats.errors));
+    }
+
     Ok(())
 }
 
-fn check_git_dir_entry(git_path: &path::PathBuf, base_dir: &path::Path) -> Result<Repository> {
-    let repo = git2::Repository::open(&git_path)?;
+/// Add the snoozes given with `--snooze` to the baseline
+fn snooze(check_opts: &CheckOptions, mut baseline: Baseline, path: &path::Path) -> Result<()> {
+    // clap requires --until with --snooze
+    let until = check_opts.until.unwrap();
+
+    for pair in check_opts.snooze.chunks(2) {
+        let repo = pair[0].trim_end_matches('/');
+
+        if !check_opts.base.base_dir.join(repo).join(".git").exists() {
+            return Err(Err::NoMatchingRepository(repo.into()));
+        }
+
+        let kind: WarningKind = pair[1].parse().map_err(|message| Err::InvalidArgument {
+            name: "--snooze",
+            message,
+        })?;
+
+        baseline.snooze(Snooze {
+            repo: repo.into(),
+            kind,
+            until,
+        });
+
+        println!("Snoozed {} warnings for {} until {}", kind, repo, until);
+    }
+
+    baseline.save(path)
+}
+
+fn check_git_dir_entry(
+    git_path: &path::PathBuf,
+    check_opts: &CheckOptions,
+    preference: &RemotePreference,
+    policy: &WarningPolicy,
+    duplicates: &HashMap<path::PathBuf, Vec<path::PathBuf>>,
+    config: &Config,
+) -> Result<Repository> {
+    let base_dir = &check_opts.base.base_dir;
+    let repo = git2::Repository::open(git_path)?;
+
+    let ignores = policy::repo_ignores(&repo)?;
+
+    let work_opts = WorkOptions {
+        match_names: check_opts.match_names,
+        fetch: check_opts.fetch,
+        // Remotes are not asked for tags that would not be reported
+        skip_tags: policy.severity(WarningKind::UnpushedTag).is_none()
+            || ignores.contains(&WarningKind::UnpushedTag),
+    };
+
+    let mut warnings = work_warnings(&repo, &work_opts)?;
+    let canonical = preference.canonical_remote(&repo)?;
+
+    if let Some(remote) = &canonical {
+        if let Ok(expected_path) = util::find_dir(base_dir, &remote.url) {
+            if expected_path != *git_path {
+                warnings.push(Warning::LocalPathDifferentFromRemote {
+                    expected_path: expected_path.normalize_relative_to(base_dir),
+                    local_path: git_path.normalize_relative_to(base_dir),
+                    remote: remote.name.clone(),
+                    url: util::redact_url(&remote.url),
+                });
+            }
+        }
+    }
+
+    warnings.extend(remote_warnings(&repo, config)?);
+
+    if let Some(host) = canonical.and_then(|remote| util::url_host(&remote.url)) {
+        warnings.extend(identity_warnings(&repo, config, &host)?);
+    }
+
+    if let Some(others) = duplicates.get(git_path) {
+        warnings.push(Warning::DuplicateClone {
+            others: others.clone(),
+        });
+    }
+
+    let mut reported = Vec::new();
+    let mut fixed = Vec::new();
+
+    for warning in warnings {
+        let severity = match policy.severity(warning.kind()) {
+            Some(severity) if !ignores.contains(&warning.kind()) => severity,
+            _ => continue,
+        };
 
+        if check_opts.fix && fix(&repo, &warning)? {
+            fixed.push(warning);
+        } else {
+            reported.push((severity, warning));
+        }
+    }
+
+    Ok(Repository {
+        path: format!("{}", git_path.normalize_relative_to(base_dir).display()),
+        warnings: reported,
+        fixed,
+    })
+}
+
+/// Warnings about identity settings that differ from the ones set for the
+/// host of the repository's preferred remote, such as
+/// `gitproject.<host>.email`
+fn identity_warnings(repo: &git2::Repository, config: &Config, host: &str) -> Result<Vec<Warning>> {
+    let repo_config = repo.config()?;
     let mut warnings = Vec::new();
 
-    if !is_clean(&repo)? {
-        warnings.push(Warning::DirtyWorkingDir);
+    for (key, setting) in IDENTITY_KEYS {
+        let expected = match config.host_values(Some(host), setting).last() {
+            Some(expected) => expected.to_string(),
+            None => continue,
+        };
+
+        let actual = repo_config.get_string(key).ok();
+
+        if !identity_matches(key, actual.as_deref(), &expected) {
+            warnings.push(Warning::WrongIdentity {
+                key,
+                actual,
+                expected,
+                host: host.to_owned(),
+            });
+        }
+    }
+
+    Ok(warnings)
+}
+
+/// Whether an identity setting has its expected value. Emails compare
+/// without case and `commit.gpgsign` compares as a boolean, where unset is
+/// false as it is for git.
+pub fn identity_matches(key: &str, actual: Option<&str>, expected: &str) -> bool {
+    match actual {
+        _ if key == "commit.gpgsign" => actual.is_some_and(parse_bool) == parse_bool(expected),
+        Some(actual) if key == "user.email" => actual.eq_ignore_ascii_case(expected),
+        Some(actual) => actual == expected,
+        None => false,
+    }
+}
+
+fn parse_bool(value: &str) -> bool {
+    matches!(value.to_lowercase().as_str(), "true" | "yes" | "on" | "1")
+}
+
+/// Resolve a warning by changing the repository's local config, returning
+/// whether it could be fixed
+fn fix(repo: &git2::Repository, warning: &Warning) -> Result<bool> {
+    match warning {
+        Warning::WrongIdentity { key, expected, .. } => {
+            let mut local = repo.config()?.open_level(git2::ConfigLevel::Local)?;
+
+            if *key == "commit.gpgsign" {
+                local.set_bool(key, parse_bool(expected))?;
+            } else {
+                local.set_str(key, expected)?;
+            }
+
+            Ok(true)
+        }
+        _ => Ok(false),
     }
+}
+
+/// Warnings about remote URLs that hold credentials, or that do not use the
+/// protocol set for their host with `gitproject.protocol`
+pub fn remote_warnings(repo: &git2::Repository, config: &Config) -> Result<Vec<Warning>> {
+    let mut warnings = Vec::new();
+
+    for name in repo.remotes()?.iter().flatten() {
+        let remote = repo.find_remote(name)?;
 
-    let remote_branches = repo.branches(Some(git2::BranchType::Remote))?;
-    let local_branches = repo.branches(Some(git2::BranchType::Local))?;
+        for url in remote.url().into_iter().chain(remote.pushurl()) {
+            if util::has_credentials(url) {
+                warnings.push(Warning::CredentialsInUrl {
+                    remote: name.to_owned(),
+                    url: util::redact_url(url),
+                });
+            }
 
-    let remotes = generate_remote_tips(strip_branch_errors(remote_branches))?;
-    let local_tips = generate_tips(strip_branch_errors(local_branches))?;
+            // Local paths have no host to set a policy for
+            let host = match util::url_host(url) {
+                Some(host) => host,
+                None => continue,
+            };
+
+            let expected: Protocol = match config.host_values(Some(&host), "protocol").last() {
+                Some(value) => value.parse().map_err(|message| Err::InvalidConfig {
+                    key: "gitproject.protocol".into(),
+                    message,
+                })?,
+                None => continue,
+            };
+
+            if util::url_protocol(url) != Some(expected) {
+                warnings.push(Warning::WrongProtocol {
+                    remote: name.to_owned(),
+                    url: util::redact_url(url),
+                    expected,
+                });
+            }
+        }
+    }
+
+    Ok(warnings)
+}
+
+/// Warnings about work in a repository that would be lost if the repository
+/// was deleted
+pub fn work_warnings(repo: &git2::Repository, opts: &WorkOptions) -> Result<Vec<Warning>> {
+    let mut warnings = Vec::new();
+
+    if !is_clean(repo)? {
+        warnings.push(Warning::DirtyWorkingDir);
+    }
 
-    if repo.remotes()?.is_empty() {
+    let remotes = repo.remotes()?;
+
+    if remotes.is_empty() {
         warnings.push(Warning::NoRemotes);
     }
 
-    for (local_branch, local_sha) in &local_tips {
-        for (remote_name, remote_tips) in &remotes {
-            match remote_tips.get(local_branch) {
-                Some(remote_sha) => {
-                    if remote_sha != local_sha {
-                        let (ahead_by, _) = repo.graph_ahead_behind(*local_sha, *remote_sha)?;
-
-                        if ahead_by > 0 {
-                            warnings.push(Warning::LocalCommitsNotOnRemote {
-                                remote: remote_name.clone(),
-                                branch: local_branch.clone(),
-                                ahead_by,
-                            });
-                        }
-                    }
-                }
-                None => {
-                    warnings.push(Warning::LocalBranchNotOnRemote {
-                        remote: remote_name.clone(),
-                        branch: local_branch.clone(),
-                    });
+    warnings.extend(upstream_warnings(repo, opts.match_names)?);
+
+    // Only the remotes know which tags they have, so they are only asked
+    // when allowed to use the network
+    if !opts.skip_tags && !remotes.is_empty() {
+        if opts.fetch {
+            let remote_names: Vec<&str> = remotes.iter().flatten().collect();
+            warnings.extend(tags_missing_from_remotes(repo, &remote_names)?);
+        } else {
+            warnings.extend(tags_not_on_remote_branches(repo)?);
+        }
+    }
+
+    let stashes = stash_count(repo);
+
+    if stashes > 0 {
+        warnings.push(Warning::Stashes { count: stashes });
+    }
+
+    if let Some(operation) = operation_in_progress(repo.state()) {
+        warnings.push(Warning::OperationInProgress { operation });
+    }
+
+    if repo.head_detached()? {
+        let commits = commits_not_on_branch(repo)?;
+
+        if commits > 0 {
+            warnings.push(Warning::DetachedHeadCommitsNotOnBranch { commits });
+        }
+    }
+
+    Ok(warnings)
+}
+
+/// Compare each local branch with its upstream. With `match_names`, branches
+/// without an upstream are compared with the branch of the same name on every
+/// remote instead.
+fn upstream_warnings(repo: &git2::Repository, match_names: bool) -> Result<Vec<Warning>> {
+    let mut warnings = Vec::new();
+    let config = repo.config()?;
+
+    let remote_tips = if match_names {
+        generate_remote_tips(strip_branch_errors(
+            repo.branches(Some(git2::BranchType::Remote))?,
+        ))?
+    } else {
+        HashMap::new()
+    };
+
+    for branch in strip_branch_errors(repo.branches(Some(git2::BranchType::Local))?) {
+        let (name, local_sha) = tip(&branch)?;
+
+        let upstream_name = match configured_upstream(&config, &name) {
+            Some(upstream_name) => upstream_name,
+            None => {
+                if match_names {
+                    warnings.extend(name_matched_warnings(repo, &name, local_sha, &remote_tips)?);
                 }
+
+                warnings.push(Warning::NoUpstream { branch: name });
+                continue;
+            }
+        };
+
+        let upstream_sha = match branch.upstream() {
+            Ok(upstream) => upstream.get().peel_to_commit()?.id(),
+            Err(_) => {
+                warnings.push(Warning::UpstreamGone {
+                    branch: name,
+                    upstream: upstream_name,
+                });
+                continue;
+            }
+        };
+
+        if upstream_sha != local_sha {
+            let (ahead_by, _) = repo.graph_ahead_behind(local_sha, upstream_sha)?;
+
+            if ahead_by > 0 {
+                warnings.push(Warning::LocalCommitsNotOnRemote {
+                    branch: name,
+                    upstream: upstream_name,
+                    ahead_by,
+                });
             }
         }
     }
 
-    if let Ok(origin) = repo.find_remote("origin") {
-        if let Some(url) = origin.url() {
-            if let Ok(expected_path) = util::find_dir(base_dir, url) {
-                if expected_path != *git_path {
-                    warnings.push(Warning::LocalPathDifferentFromOrigin {
-                        expected_path: expected_path.normalize_relative_to(base_dir),
-                        local_path: git_path.normalize_relative_to(base_dir),
-                        origin: url.into(),
-                    });
+    Ok(warnings)
+}
+
+/// The short name of the upstream configured for a local branch with
+/// `branch.<name>.remote` and `branch.<name>.merge`, whether or not it exists
+pub fn configured_upstream(config: &git2::Config, branch: &str) -> Option<String> {
+    let remote = config
+        .get_string(&format!("branch.{}.remote", branch))
+        .ok()?;
+    let merge = config
+        .get_string(&format!("branch.{}.merge", branch))
+        .ok()?;
+    let merge = merge.trim_start_matches("refs/heads/");
+
+    if remote == "." {
+        Some(merge.to_owned())
+    } else {
+        Some(format!("{}/{}", remote, merge))
+    }
+}
+
+/// Compare a local branch with the branch of the same name on every remote
+fn name_matched_warnings(
+    repo: &git2::Repository,
+    branch: &str,
+    local_sha: git2::Oid,
+    remote_tips: &HashMap<String, HashMap<String, git2::Oid>>,
+) -> Result<Vec<Warning>> {
+    let mut warnings = Vec::new();
+
+    for (remote_name, tips) in remote_tips {
+        match tips.get(branch) {
+            Some(remote_sha) => {
+                if *remote_sha != local_sha {
+                    let (ahead_by, _) = repo.graph_ahead_behind(local_sha, *remote_sha)?;
+
+                    if ahead_by > 0 {
+                        warnings.push(Warning::LocalCommitsNotOnRemote {
+                            branch: branch.to_owned(),
+                            upstream: format!("{}/{}", remote_name, branch),
+                            ahead_by,
+                        });
+                    }
                 }
             }
+            None => {
+                warnings.push(Warning::LocalBranchNotOnRemote {
+                    remote: remote_name.clone(),
+                    branch: branch.to_owned(),
+                });
+            }
         }
     }
 
-    Ok(Repository {
-        path: format!("{}", git_path.normalize_relative_to(base_dir).display()),
-        warnings,
-    })
+    Ok(warnings)
+}
+
+/// Local tags and the commits they point to, skipping tags of anything other
+/// than a commit
+fn local_tags(repo: &git2::Repository) -> Result<Vec<(String, git2::Oid)>> {
+    let mut tags = Vec::new();
+
+    for name in repo.tag_names(None)?.iter().flatten() {
+        let target = repo
+            .revparse_single(&format!("refs/tags/{}", name))
+            .and_then(|object| object.peel_to_commit());
+
+        if let Ok(commit) = target {
+            tags.push((name.to_owned(), commit.id()));
+        }
+    }
+
+    Ok(tags)
+}
+
+fn unpushed_tag(tag: String, target: git2::Oid) -> Warning {
+    let mut target = target.to_string();
+    target.truncate(7);
+
+    Warning::UnpushedTag { tag, target }
+}
+
+/// Tags that `git ls-remote` does not list for any of the remotes. A remote
+/// that cannot be asked gets a warning of its own, and its tags are treated
+/// as unknown.
+fn tags_missing_from_remotes(repo: &git2::Repository, remotes: &[&str]) -> Result<Vec<Warning>> {
+    let tags = local_tags(repo)?;
+
+    if tags.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let work_dir = repo.workdir().unwrap_or_else(|| repo.path());
+    let mut remote_tags = HashSet::new();
+    let mut warnings = Vec::new();
+
+    for remote in remotes {
+        let output = process::Command::new("git")
+            .args(["ls-remote", "--tags", remote])
+            .current_dir(work_dir)
+            .stderr(process::Stdio::null())
+            .output()?;
+
+        if !output.status.success() {
+            warnings.push(Warning::TagsUnknown {
+                remote: (*remote).to_owned(),
+            });
+            continue;
+        }
+
+        remote_tags.extend(advertised_tags(&String::from_utf8_lossy(&output.stdout)));
+    }
+
+    warnings.extend(
+        tags.into_iter()
+            .filter(|(tag, _)| !remote_tags.contains(tag))
+            .map(|(tag, target)| unpushed_tag(tag, target)),
+    );
+
+    Ok(warnings)
+}
+
+/// Tags whose commit no remote-tracking branch contains, which is what the
+/// last fetch knows of the remotes without asking them
+fn tags_not_on_remote_branches(repo: &git2::Repository) -> Result<Vec<Warning>> {
+    let tags = local_tags(repo)?;
+
+    if tags.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut revwalk = repo.revwalk()?;
+
+    for (_, target) in &tags {
+        revwalk.push(*target)?;
+    }
+
+    for reference in repo.references_glob("refs/remotes/*")?.flatten() {
+        if let Ok(commit) = reference.peel_to_commit() {
+            revwalk.hide(commit.id())?;
+        }
+    }
+
+    let unpushed = revwalk.collect::<std::result::Result<HashSet<_>, _>>()?;
+
+    Ok(tags
+        .into_iter()
+        .filter(|(_, target)| unpushed.contains(target))
+        .map(|(tag, target)| unpushed_tag(tag, target))
+        .collect())
+}
+
+/// Tag names in the output of `git ls-remote --tags`, including the peeled
+/// `^{}` entries of annotated tags under their plain name
+pub fn advertised_tags(ls_remote: &str) -> HashSet<String> {
+    ls_remote
+        .lines()
+        .filter_map(|line| line.split('\t').nth(1))
+        .filter_map(|name| name.strip_prefix("refs/tags/"))
+        .map(|name| name.trim_end_matches("^{}").to_owned())
+        .collect()
+}
+
+fn stash_count(repo: &git2::Repository) -> usize {
+    if repo.find_reference("refs/stash").is_err() {
+        return 0;
+    }
+
+    repo.reflog("refs/stash").map(|log| log.len()).unwrap_or(0)
 }
 
-fn is_clean(repo: &git2::Repository) -> Result<bool> {
+fn operation_in_progress(state: git2::RepositoryState) -> Option<&'static str> {
+    use git2::RepositoryState::*;
+
+    match state {
+        Clean => None,
+        Merge => Some("merge"),
+        Revert | RevertSequence => Some("revert"),
+        CherryPick | CherryPickSequence => Some("cherry-pick"),
+        Bisect => Some("bisect"),
+        Rebase | RebaseInteractive | RebaseMerge => Some("rebase"),
+        ApplyMailbox | ApplyMailboxOrRebase => Some("am"),
+    }
+}
+
+/// Number of commits reachable from HEAD that no local or remote branch
+/// contains
+fn commits_not_on_branch(repo: &git2::Repository) -> Result<usize> {
+    let head = match repo.head()?.target() {
+        Some(oid) => oid,
+        None => return Ok(0),
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head)?;
+
+    for branch in strip_branch_errors(repo.branches(None)?) {
+        if let Some(tip) = branch.get().target() {
+            revwalk.hide(tip)?;
+        }
+    }
+
+    Ok(revwalk.count())
+}
+
+pub fn is_clean(repo: &git2::Repository) -> Result<bool> {
     let statuses = repo.statuses(Some(git2::StatusOptions::new().include_untracked(true)))?;
 
     for status in statuses.iter() {
@@ -242,7 +933,7 @@ fn is_clean(repo: &git2::Repository) -> Result<bool> {
     Ok(true)
 }
 
-fn strip_branch_errors(branches: git2::Branches) -> impl Iterator<Item = git2::Branch> {
+pub fn strip_branch_errors(branches: git2::Branches) -> impl Iterator<Item = git2::Branch> {
     branches.filter_map(|x| x.map(|(branch, _)| branch).ok())
 }
 
@@ -277,20 +968,6 @@ where
     Ok(map)
 }
 
-fn generate_tips<'a, I>(branches: I) -> Result<HashMap<String, git2::Oid>>
-where
-    I: Iterator<Item = git2::Branch<'a>>,
-{
-    let mut map = HashMap::new();
-
-    for branch in branches {
-        let (ref_name, commit) = tip(&branch)?;
-        map.insert(ref_name, commit);
-    }
-
-    Ok(map)
-}
-
 fn tip<'a>(branch: &git2::Branch<'a>) -> Result<(String, git2::Oid)> {
     let branch_ref = branch.get();
 
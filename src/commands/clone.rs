@@ -1,12 +1,14 @@
 use crate::{
     err::{Err, Result},
+    explore,
     options::CloneOptions,
+    remotes::RemotePreference,
     util,
 };
 use std::{fs, path, process};
 
 pub fn run(clone_opts: &CloneOptions) -> Result<()> {
-    let full_path = util::find_dir(&clone_opts.base.base_dir, &clone_opts.clone_url)?;
+    let full_path = location(clone_opts)?;
 
     if clone_opts.only_print_location {
         println!("{}", full_path.display());
@@ -19,6 +21,57 @@ pub fn run(clone_opts: &CloneOptions) -> Result<()> {
     Ok(())
 }
 
+/// Where a URL is cloned to. A repository under the URL's host that already
+/// has the URL as one of its remotes, such as a fork added next to
+/// `upstream`, is where its preferred remote puts it rather than where the
+/// URL would. Repositories that cannot be read are passed over.
+pub fn location(clone_opts: &CloneOptions) -> Result<path::PathBuf> {
+    let base_dir = &clone_opts.base.base_dir;
+    let url_path = util::find_dir(base_dir, &clone_opts.clone_url)?;
+
+    if url_path.exists() {
+        return Ok(url_path);
+    }
+
+    let host_dir = match util::url_host(&clone_opts.clone_url) {
+        Some(host) => base_dir.join(host),
+        None => return Ok(url_path),
+    };
+
+    if !host_dir.is_dir() {
+        return Ok(url_path);
+    }
+
+    let preference = RemotePreference::load(&clone_opts.remote)?;
+
+    for dir in explore::find_git_folders(&host_dir, false)? {
+        let repo = match git2::Repository::open(&dir) {
+            Ok(repo) => repo,
+            Err(_) => continue,
+        };
+
+        let remotes = match repo.remotes() {
+            Ok(remotes) => remotes,
+            Err(_) => continue,
+        };
+
+        let has_url = remotes.iter().flatten().any(|name| {
+            repo.find_remote(name)
+                .ok()
+                .and_then(|remote| remote.url().map(|url| util::find_dir(base_dir, url)))
+                .is_some_and(|path| path.ok().as_ref() == Some(&url_path))
+        });
+
+        if has_url {
+            if let Ok(Some(remote)) = preference.canonical_remote(&repo) {
+                return util::find_dir(base_dir, &remote.url);
+            }
+        }
+    }
+
+    Ok(url_path)
+}
+
 pub fn clone<P>(clone_opts: &CloneOptions, clone_path: P) -> Result<()>
 where
     P: AsRef<path::Path>,
@@ -33,7 +86,7 @@ where
     fs::create_dir_all(clone_path.parent().unwrap()).expect("Failed to create directory");
 
     let status = process::Command::new("git")
-        .args(&["clone", &clone_opts.clone_url, clone_path.to_str().unwrap()])
+        .args(["clone", &clone_opts.clone_url, clone_path.to_str().unwrap()])
         .stdout(process::Stdio::null())
         .status()?;
 
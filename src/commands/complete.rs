@@ -0,0 +1,145 @@
+use crate::{
+    err::Result,
+    explore,
+    options::{CompleteOptions, CompletionKind},
+    util::PathRelativizeExtension,
+};
+use std::{collections::BTreeSet, path};
+
+/// Subcommands whose positional arguments are completed, with how many
+/// positional arguments come before the completed ones and the kinds they
+/// are completed with
+pub const POSITIONAL_COMPLETIONS: &[(&[&str], usize, &[CompletionKind])] = &[
+    (&["mv"], 0, &[CompletionKind::Repos]),
+    (&["rm"], 0, &[CompletionKind::Repos]),
+    (&["where"], 0, &[CompletionKind::Repos]),
+    // Repositories follow the branch
+    (&["switch"], 1, &[CompletionKind::Repos]),
+    // Member patterns follow the group name, and a host or owner selects
+    // every repository under it
+    (
+        &["group", "add"],
+        1,
+        &[
+            CompletionKind::Hosts,
+            CompletionKind::Owners,
+            CompletionKind::Repos,
+        ],
+    ),
+    (
+        &["group", "remove"],
+        1,
+        &[
+            CompletionKind::Hosts,
+            CompletionKind::Owners,
+            CompletionKind::Repos,
+        ],
+    ),
+];
+
+/// Options that take a value, which is not a positional argument
+pub const VALUE_OPTIONS: &[&str] = &["-d", "--base-dir", "--remote", "--group"];
+
+pub fn run(opts: &CompleteOptions) -> Result<()> {
+    let candidates = match opts.kind {
+        Some(kind) => candidates(&opts.base.base_dir, kind, &opts.prefix)?,
+        None => line_candidates(&opts.base.base_dir, &opts.line)?,
+    };
+
+    for candidate in candidates {
+        println!("{}", candidate);
+    }
+
+    Ok(())
+}
+
+/// The completions of a kind that start with `prefix`, sorted and without
+/// duplicates
+pub fn candidates(
+    base_dir: &path::Path,
+    kind: CompletionKind,
+    prefix: &str,
+) -> Result<BTreeSet<String>> {
+    let mut candidates = BTreeSet::new();
+
+    for dir in explore::find_git_folders(base_dir, false)? {
+        let relative = dir.normalize_relative_to(base_dir);
+        let components: Vec<_> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+
+        match kind {
+            CompletionKind::Repos => {
+                candidates.insert(components.join("/"));
+            }
+            CompletionKind::Hosts => {
+                if components.len() > 1 {
+                    candidates.insert(components[0].clone());
+                }
+            }
+            CompletionKind::Owners => {
+                if components.len() > 2 {
+                    candidates.insert(components[..2].join("/"));
+                }
+            }
+            CompletionKind::Remotes => {
+                if let Ok(repo) = git2::Repository::open(&dir) {
+                    for name in repo.remotes()?.iter().flatten() {
+                        candidates.insert(name.to_owned());
+                    }
+                }
+            }
+        }
+    }
+
+    candidates.retain(|candidate| candidate.starts_with(prefix));
+
+    Ok(candidates)
+}
+
+/// The completions of the last word of a command line, given without the
+/// leading `git-project`
+pub fn line_candidates(base_dir: &path::Path, words: &[String]) -> Result<BTreeSet<String>> {
+    let mut candidates = BTreeSet::new();
+
+    if let Some((current, before)) = words.split_last() {
+        for kind in line_kinds(before, current) {
+            candidates.extend(self::candidates(base_dir, *kind, current)?);
+        }
+    }
+
+    Ok(candidates)
+}
+
+/// The kinds to complete `current` with, after the words `before` it. Options
+/// and words the shell completes statically have none.
+pub fn line_kinds<S: AsRef<str>>(before: &[S], current: &str) -> &'static [CompletionKind] {
+    match before.last().map(AsRef::as_ref) {
+        Some("--remote") => return &[CompletionKind::Remotes],
+        Some(previous) if VALUE_OPTIONS.contains(&previous) => return &[],
+        _ => {}
+    }
+
+    if current.starts_with('-') {
+        return &[];
+    }
+
+    let mut positionals = Vec::new();
+    let mut words = before.iter().map(AsRef::as_ref);
+
+    while let Some(word) = words.next() {
+        if VALUE_OPTIONS.contains(&word) {
+            words.next();
+        } else if !word.starts_with('-') {
+            positionals.push(word);
+        }
+    }
+
+    POSITIONAL_COMPLETIONS
+        .iter()
+        .find(|(subcommand, preceding, _)| {
+            positionals.starts_with(subcommand) && positionals.len() >= subcommand.len() + preceding
+        })
+        .map_or(&[], |(_, _, kinds)| kinds)
+}
@@ -0,0 +1,219 @@
+use crate::{
+    commands::check,
+    err::Result,
+    explore,
+    options::DuplicatesOptions,
+    remotes::RemotePreference,
+    util::{self, PathRelativizeExtension},
+};
+use rayon::prelude::*;
+use std::{collections::BTreeMap, path};
+
+/// Repositories whose preferred remotes normalize to the same location
+pub struct Group {
+    /// Where the repository belongs according to its remote
+    pub canonical_path: path::PathBuf,
+    pub url: String,
+    pub copies: Vec<path::PathBuf>,
+}
+
+/// How a copy compares with the copy it is measured against
+#[derive(Debug, PartialEq)]
+pub enum Divergence {
+    Same,
+    Behind,
+    Ahead,
+    Diverged,
+    /// One of the copies has no commits
+    Unknown,
+}
+
+/// One clone in a group of duplicates, as it is checked out
+pub struct Checkout {
+    path: path::PathBuf,
+    branch: Option<String>,
+    head: Option<git2::Oid>,
+    dirty: bool,
+}
+
+pub fn run(opts: &DuplicatesOptions) -> Result<()> {
+    let base_dir = &opts.base.base_dir;
+    let paths = explore::find_repositories(base_dir, &opts.list)?;
+    let preference = RemotePreference::load(&opts.remote)?;
+
+    let groups = find_duplicates(&paths, base_dir, &preference);
+
+    let reports: Vec<String> = groups
+        .par_iter()
+        .map(|group| report(group, base_dir))
+        .filter_map(|result| match result {
+            Ok(x) => Some(x),
+            Err(e) => {
+                eprintln!("Error received: {}", e);
+                None
+            }
+        })
+        .collect();
+
+    for report in reports {
+        println!("{}", report);
+    }
+
+    Ok(())
+}
+
+/// Group repositories by the normalized URL of their preferred remote, the
+/// same way `clone` decides where a repository belongs, keeping only groups
+/// with more than one copy
+pub fn find_duplicates(
+    paths: &[path::PathBuf],
+    base_dir: &path::Path,
+    preference: &RemotePreference,
+) -> Vec<Group> {
+    let located: Vec<(path::PathBuf, String, path::PathBuf)> = paths
+        .par_iter()
+        .filter_map(|dir| {
+            let repo = git2::Repository::open(dir).ok()?;
+            let remote = preference.canonical_remote(&repo).ok()??;
+            let canonical_path = util::find_dir(base_dir, &remote.url).ok()?;
+
+            Some((canonical_path, util::redact_url(&remote.url), dir.clone()))
+        })
+        .collect();
+
+    let mut groups: BTreeMap<path::PathBuf, Group> = BTreeMap::new();
+
+    for (canonical_path, url, dir) in located {
+        groups
+            .entry(canonical_path.clone())
+            .or_insert_with(|| Group {
+                canonical_path,
+                url,
+                copies: Vec::new(),
+            })
+            .copies
+            .push(dir);
+    }
+
+    groups
+        .into_values()
+        .filter(|group| group.copies.len() > 1)
+        .collect()
+}
+
+/// Describe every copy in a group, compared with the copy at the canonical
+/// path, or with the first copy if none is there
+fn report(group: &Group, base_dir: &path::Path) -> Result<String> {
+    let mut copies = group
+        .copies
+        .iter()
+        .map(|path| checkout(path))
+        .collect::<Result<Vec<Checkout>>>()?;
+
+    copies.sort_by_key(|c| c.path != group.canonical_path);
+
+    let mut lines = vec![format!(
+        "{} ({})",
+        group
+            .canonical_path
+            .normalize_relative_to(base_dir)
+            .display(),
+        group.url
+    )];
+
+    let reference = &copies[0];
+    let reference_is_canonical = reference.path == group.canonical_path;
+
+    for c in &copies {
+        let mut details = vec![format!(
+            "{} at {}",
+            c.branch.as_deref().unwrap_or("(detached)"),
+            c.head.map_or("(no commits)".into(), short_id)
+        )];
+
+        if c.path == reference.path {
+            if reference_is_canonical {
+                details.push("canonical path".into());
+            }
+        } else {
+            let compared_with = if reference_is_canonical {
+                "canonical copy"
+            } else {
+                "first copy"
+            };
+
+            details.push(match divergence(reference, c) {
+                Divergence::Same => format!("same commit as {}", compared_with),
+                Divergence::Behind => format!("behind {}", compared_with),
+                Divergence::Ahead => format!("ahead of {}", compared_with),
+                Divergence::Diverged => format!("diverged from {}", compared_with),
+                Divergence::Unknown => format!("nothing to compare with {}", compared_with),
+            });
+        }
+
+        if c.dirty {
+            details.push("dirty".into());
+        }
+
+        lines.push(format!(
+            "  - {}: {}",
+            c.path.normalize_relative_to(base_dir).display(),
+            details.join(", ")
+        ));
+    }
+
+    Ok(lines.join("\n") + "\n")
+}
+
+pub fn checkout(path: &path::Path) -> Result<Checkout> {
+    let repo = git2::Repository::open(path)?;
+    let head = repo.head().ok();
+
+    Ok(Checkout {
+        path: path.to_path_buf(),
+        branch: head
+            .as_ref()
+            .filter(|h| h.is_branch())
+            .and_then(|h| h.shorthand())
+            .map(String::from),
+        head: head.and_then(|h| h.target()),
+        dirty: !check::is_clean(&repo)?,
+    })
+}
+
+fn short_id(oid: git2::Oid) -> String {
+    let mut id = oid.to_string();
+    id.truncate(7);
+    id
+}
+
+/// Compare the HEAD commits of two copies. Each copy only knows the commits
+/// it has fetched, so both are asked whether they contain the other's HEAD,
+/// and copies where neither does are counted as diverged.
+pub fn divergence(reference: &Checkout, other: &Checkout) -> Divergence {
+    let (reference_head, other_head) = match (reference.head, other.head) {
+        (Some(r), Some(o)) => (r, o),
+        _ => return Divergence::Unknown,
+    };
+
+    if reference_head == other_head {
+        return Divergence::Same;
+    }
+
+    if contains(&reference.path, reference_head, other_head) {
+        return Divergence::Behind;
+    }
+
+    if contains(&other.path, other_head, reference_head) {
+        return Divergence::Ahead;
+    }
+
+    Divergence::Diverged
+}
+
+/// Whether `ancestor` is in the history of `head` in the repository at `path`
+fn contains(path: &path::Path, head: git2::Oid, ancestor: git2::Oid) -> bool {
+    git2::Repository::open(path)
+        .and_then(|repo| repo.graph_descendant_of(head, ancestor))
+        .unwrap_or(false)
+}
@@ -1,12 +1,112 @@
 use crate::{err::Result, options};
-use structopt::StructOpt;
+use structopt::{clap, StructOpt};
+
+// The dynamic parts of the scripts pass the command line to
+// `git-project complete`, which knows the arguments that take repositories,
+// hosts, owners and remotes, and defer to the static clap completions when
+// it has no candidates.
+
+const BASH_DYNAMIC: &str = r#"
+_git_project_dynamic() {
+    local i
+    local -a base_dir candidates
+
+    for ((i = 2; i < COMP_CWORD; i++)); do
+        case "${COMP_WORDS[i]}" in
+            -d|--base-dir)
+                base_dir=(--base-dir "${COMP_WORDS[i+1]}")
+                ;;
+        esac
+    done
+
+    local IFS=$'\n'
+    candidates=($(git-project complete "${base_dir[@]}" -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null))
+
+    if [[ ${#candidates[@]} -gt 0 ]]; then
+        COMPREPLY=("${candidates[@]}")
+        return 0
+    fi
+
+    _git-project "$@"
+}
+
+complete -F _git_project_dynamic -o bashdefault -o default git-project
+"#;
+
+const ZSH_DYNAMIC: &str = r#"
+_git_project_dynamic() {
+    local i
+    local -a base_dir candidates
+
+    for ((i = 3; i < CURRENT; i++)); do
+        case "${words[i]}" in
+            (-d|--base-dir)
+                base_dir=(--base-dir "${words[i+1]}")
+                ;;
+        esac
+    done
+
+    candidates=(${(f)"$(git-project complete "${base_dir[@]}" -- "${(@)words[2,CURRENT]}" 2>/dev/null)"})
+
+    if (( ${#candidates} )); then
+        compadd -a candidates
+        return
+    fi
+
+    _git-project "$@"
+}
+
+_git_project_dynamic "$@"
+"#;
+
+const FISH_DYNAMIC: &str = r#"
+function __git_project_complete
+    set -l tokens (commandline -opc)
+    set -l base_dir
+    for i in (seq (math (count $tokens) - 1))
+        if contains -- $tokens[$i] -d --base-dir
+            set base_dir --base-dir $tokens[(math $i + 1)]
+        end
+    end
+    git-project complete $base_dir -- $tokens[2..-1] (commandline -ct) 2>/dev/null
+end
+
+function __git_project_has_candidates
+    set -l candidates (__git_project_complete)
+    test (count $candidates) -gt 0
+end
+
+complete -c git-project -n __git_project_has_candidates -f -a "(__git_project_complete)"
+"#;
 
 pub fn run(opts: &options::CompletionOptions) -> Result<()> {
-    options::Options::clap().gen_completions_to(
-        "git-project",
-        opts.shell.into(),
-        &mut std::io::stdout(),
-    );
+    print!("{}", script(opts.shell));
 
     Ok(())
 }
+
+/// The clap completions for a shell, followed by the dynamic part for the
+/// shells that have one
+pub fn script(shell: clap::Shell) -> String {
+    let mut script = Vec::new();
+    options::Options::clap().gen_completions_to("git-project", shell, &mut script);
+    let mut script = String::from_utf8_lossy(&script).into_owned();
+
+    let dynamic = match shell {
+        clap::Shell::Bash => BASH_DYNAMIC,
+        clap::Shell::Zsh => {
+            // The clap script ends by calling its completion function, which
+            // the dynamic function calls instead
+            if let Some(i) = script.rfind("_git-project \"$@\"") {
+                script.truncate(i);
+            }
+
+            ZSH_DYNAMIC
+        }
+        clap::Shell::Fish => FISH_DYNAMIC,
+        _ => "",
+    };
+
+    script.push_str(dynamic);
+    script
+}
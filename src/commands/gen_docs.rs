@@ -0,0 +1,241 @@
+use crate::{
+    err::{Err, Result},
+    options,
+};
+use std::{fs, io::prelude::*, iter};
+use structopt::{clap, StructOpt};
+
+/// Help text is wrapped to this width, whatever the terminal's
+const WIDTH: usize = 100;
+
+/// Everything shown about one command, taken from its `--help` output
+pub struct Page {
+    /// The full command name, such as `git-project-clone`
+    name: String,
+    /// How the command is invoked, such as `git project clone`
+    invocation: String,
+    /// The name of the command this is a subcommand of
+    parent: Option<String>,
+    about: String,
+    help: String,
+    subcommands: Vec<(String, String)>,
+}
+
+pub fn run(opts: &options::DocsOptions) -> Result<()> {
+    let pages = pages(
+        &options::Options::clap(),
+        "git-project",
+        "git project",
+        env!("CARGO_PKG_DESCRIPTION"),
+    )?;
+
+    fs::create_dir_all(&opts.out_dir)?;
+
+    let mut markdown = fs::File::create(opts.out_dir.join("git-project.md"))?;
+
+    for (page, level) in &pages {
+        let mut man = fs::File::create(opts.out_dir.join(format!("{}.1", page.name)))?;
+        write_man_page(&mut man, page)?;
+        write_markdown_page(&mut markdown, page, *level)?;
+    }
+
+    println!(
+        "Wrote {} man pages and git-project.md to {}",
+        pages.len(),
+        opts.out_dir.display()
+    );
+
+    Ok(())
+}
+
+/// The page of an App and, after each page, those of its visible
+/// subcommands, with the markdown heading level of each
+pub fn pages(
+    app: &clap::App,
+    name: &str,
+    invocation: &str,
+    about: &str,
+) -> Result<Vec<(Page, usize)>> {
+    let app = app.clone().set_term_width(WIDTH);
+    let mut pages = Vec::new();
+
+    add_pages(
+        &app,
+        &mut Vec::new(),
+        Page {
+            name: name.into(),
+            invocation: invocation.into(),
+            parent: None,
+            about: about.into(),
+            help: String::new(),
+            subcommands: Vec::new(),
+        },
+        1,
+        &mut pages,
+    )?;
+
+    Ok(pages)
+}
+
+/// Fill in the help of a page, which is reached through the subcommands in
+/// `path`, then add it and the pages of its subcommands
+fn add_pages(
+    app: &clap::App,
+    path: &mut Vec<String>,
+    mut page: Page,
+    level: usize,
+    pages: &mut Vec<(Page, usize)>,
+) -> Result<()> {
+    page.help = long_help(app, path)?;
+    page.subcommands = subcommands(&page.help);
+
+    let subcommands = page.subcommands.clone();
+    let name = page.name.clone();
+    let invocation = page.invocation.clone();
+
+    pages.push((page, level));
+
+    for (sub, about) in subcommands {
+        let sub_page = Page {
+            name: format!("{}-{}", name, sub),
+            invocation: format!("{} {}", invocation, sub),
+            parent: Some(name.clone()),
+            about,
+            help: String::new(),
+            subcommands: Vec::new(),
+        };
+
+        path.push(sub);
+        add_pages(app, path, sub_page, level + 1, pages)?;
+        path.pop();
+    }
+
+    Ok(())
+}
+
+/// The long help of the subcommand at `path`, as `--help` prints it
+fn long_help(app: &clap::App, path: &[String]) -> Result<String> {
+    let args = iter::once(app.get_name())
+        .chain(path.iter().map(String::as_str))
+        .chain(iter::once("--help"));
+
+    match app.clone().get_matches_from_safe(args) {
+        Ok(_) => Err(Err::InvalidArgument {
+            name: "subcommand",
+            message: format!("{} printed no help", path.join(" ")),
+        }),
+        Err(e) => match e.kind {
+            clap::ErrorKind::HelpDisplayed => Ok(e
+                .message
+                .trim_end()
+                .lines()
+                .map(str::trim_end)
+                .collect::<Vec<_>>()
+                .join("\n")),
+            _ => Err(Err::InvalidArgument {
+                name: "subcommand",
+                message: e.message,
+            }),
+        },
+    }
+}
+
+/// The visible subcommands listed in a help text with their descriptions,
+/// leaving out `help`
+fn subcommands(help: &str) -> Vec<(String, String)> {
+    let mut subcommands: Vec<(String, String)> = Vec::new();
+    let listing = help
+        .lines()
+        .skip_while(|line| *line != "SUBCOMMANDS:")
+        .skip(1)
+        .take_while(|line| !line.is_empty());
+
+    for line in listing {
+        let text = line.trim_start();
+
+        // Descriptions too long for one line continue further indented
+        if line.len() - text.len() > 4 {
+            if let Some((_, about)) = subcommands.last_mut() {
+                about.push(' ');
+                about.push_str(text);
+            }
+            continue;
+        }
+
+        let mut parts = text.splitn(2, ' ');
+        let name = parts.next().unwrap_or("");
+        let about = parts.next().unwrap_or("").trim_start();
+
+        subcommands.push((name.into(), about.into()));
+    }
+
+    subcommands.retain(|(name, _)| name != "help");
+    subcommands
+}
+
+/// Escape text for roff, so that no line of it is taken as a request
+fn roff(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let escaped = line.replace('\\', "\\e").replace('-', "\\-");
+
+            if escaped.starts_with('.') || escaped.starts_with('\'') {
+                format!("\\&{}", escaped)
+            } else {
+                escaped
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn write_man_page<W: Write>(out: &mut W, page: &Page) -> Result<()> {
+    writeln!(
+        out,
+        ".TH {} 1 \"\" \"git-project {}\" \"Git Manual\"",
+        roff(&page.name.to_uppercase()),
+        roff(env!("CARGO_PKG_VERSION"))
+    )?;
+
+    writeln!(out, ".SH NAME")?;
+    writeln!(out, "{} \\- {}", roff(&page.name), roff(&page.about))?;
+
+    writeln!(out, ".SH DESCRIPTION")?;
+    writeln!(out, ".nf")?;
+    writeln!(out, "{}", roff(&page.help))?;
+    writeln!(out, ".fi")?;
+
+    if !page.subcommands.is_empty() {
+        writeln!(out, ".SH COMMANDS")?;
+
+        for (name, about) in &page.subcommands {
+            writeln!(out, ".TP")?;
+            writeln!(
+                out,
+                "\\fB{}\\fR(1)",
+                roff(&format!("{}-{}", page.name, name))
+            )?;
+            writeln!(out, "{}", roff(about))?;
+        }
+    }
+
+    if let Some(parent) = &page.parent {
+        writeln!(out, ".SH SEE ALSO")?;
+        writeln!(out, "\\fB{}\\fR(1)", roff(parent))?;
+    }
+
+    Ok(())
+}
+
+pub fn write_markdown_page<W: Write>(out: &mut W, page: &Page, level: usize) -> Result<()> {
+    writeln!(out, "{} `{}`", "#".repeat(level), page.invocation)?;
+    writeln!(out)?;
+    writeln!(out, "{}", page.about)?;
+    writeln!(out)?;
+    writeln!(out, "```")?;
+    writeln!(out, "{}", page.help)?;
+    writeln!(out, "```")?;
+    writeln!(out)?;
+
+    Ok(())
+}
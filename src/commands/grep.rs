@@ -0,0 +1,202 @@
+use crate::{
+    err::{Err, Result},
+    explore,
+    options::GrepOptions,
+    util::{PathFilter, PathRelativizeExtension},
+};
+use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
+use std::{fs, path};
+
+/// Files with a NUL byte this close to their start are treated as binary
+const BINARY_CHECK_LEN: usize = 8000;
+
+/// Git file mode of submodules, which have no content in the repository
+const GITLINK_MODE: u32 = 0o160_000;
+
+/// Git file mode of symbolic links, whose content is the link target
+const SYMLINK_MODE: u32 = 0o120_000;
+
+pub struct Repository {
+    pub path: path::PathBuf,
+    pub files: Vec<File>,
+}
+
+pub struct File {
+    pub path: String,
+    /// Line numbers and lines that match
+    pub lines: Vec<(usize, String)>,
+}
+
+pub fn run(opts: &GrepOptions) -> Result<()> {
+    let base_dir = &opts.base.base_dir;
+    let paths = explore::find_repositories(base_dir, &opts.list)?;
+
+    let pattern = if opts.fixed_strings {
+        regex::escape(&opts.pattern)
+    } else {
+        opts.pattern.clone()
+    };
+
+    let regex = RegexBuilder::new(&pattern)
+        .case_insensitive(opts.ignore_case)
+        .build()
+        .map_err(|e| Err::InvalidArgument {
+            name: "PATTERN",
+            message: e.to_string(),
+        })?;
+
+    let filter = PathFilter::new(&opts.paths, "PATH")?;
+
+    let mut repos: Vec<Repository> = paths
+        .par_iter()
+        .map(|dir| search(dir, opts.rev.as_deref(), &regex, &filter))
+        .filter_map(|result| match result {
+            Ok(x) => Some(x),
+            Err(e) => {
+                eprintln!("Error received: {}", e);
+                None
+            }
+        })
+        .filter(|repo| !repo.files.is_empty())
+        .collect();
+
+    repos.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut total = 0;
+
+    for repo in &repos {
+        println!("{}", repo.path.normalize_relative_to(base_dir).display());
+
+        for file in &repo.files {
+            if opts.files_with_matches {
+                println!("  {}", file.path);
+            } else {
+                for (number, line) in &file.lines {
+                    println!("  {}:{}: {}", file.path, number, line);
+                }
+            }
+
+            total += file.lines.len();
+        }
+
+        println!();
+    }
+
+    println!("{} matches in {} repositories", total, repos.len());
+
+    Ok(())
+}
+
+/// Search the tracked files of a repository, either as they are in the
+/// working tree or at a revision
+pub fn search(
+    dir: &path::Path,
+    rev: Option<&str>,
+    regex: &Regex,
+    filter: &PathFilter,
+) -> Result<Repository> {
+    let repo = git2::Repository::open(dir)?;
+    let mut files = Vec::new();
+
+    let mut search_content = |path: String, content: &[u8]| {
+        let lines = matching_lines(regex, content);
+
+        if !lines.is_empty() {
+            files.push(File { path, lines });
+        }
+    };
+
+    match rev {
+        Some(rev) => {
+            // Repositories without the revision have nothing to search
+            let tree = match repo.revparse_single(rev).and_then(|o| o.peel_to_tree()) {
+                Ok(tree) => tree,
+                Err(_) => {
+                    return Ok(Repository {
+                        path: dir.to_path_buf(),
+                        files,
+                    })
+                }
+            };
+
+            let mut blobs = Vec::new();
+            tree_blobs(&repo, &tree, "", &mut blobs)?;
+
+            for (path, id) in blobs {
+                if filter.matches(&path) {
+                    search_content(path, repo.find_blob(id)?.content());
+                }
+            }
+        }
+        None => {
+            let workdir = match repo.workdir() {
+                Some(workdir) => workdir.to_path_buf(),
+                None => dir.to_path_buf(),
+            };
+
+            for entry in repo.index()?.iter() {
+                if entry.mode == GITLINK_MODE || entry.mode == SYMLINK_MODE {
+                    continue;
+                }
+
+                let path = String::from_utf8_lossy(&entry.path).into_owned();
+
+                if !filter.matches(&path) {
+                    continue;
+                }
+
+                // Tracked files deleted from the working tree are skipped
+                if let Ok(content) = fs::read(workdir.join(&path)) {
+                    search_content(path, &content);
+                }
+            }
+        }
+    }
+
+    Ok(Repository {
+        path: dir.to_path_buf(),
+        files,
+    })
+}
+
+/// Collect the paths and ids of every file in a tree and its subtrees
+fn tree_blobs(
+    repo: &git2::Repository,
+    tree: &git2::Tree,
+    prefix: &str,
+    blobs: &mut Vec<(String, git2::Oid)>,
+) -> Result<()> {
+    for entry in tree.iter() {
+        let path = format!("{}{}", prefix, String::from_utf8_lossy(entry.name_bytes()));
+
+        match entry.kind() {
+            Some(git2::ObjectType::Tree) => {
+                let subtree = repo.find_tree(entry.id())?;
+                tree_blobs(repo, &subtree, &format!("{}/", path), blobs)?;
+            }
+            Some(git2::ObjectType::Blob) if entry.filemode() as u32 != SYMLINK_MODE => {
+                blobs.push((path, entry.id()));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// The numbered lines of a file that match, or none for binary files
+pub fn matching_lines(regex: &Regex, content: &[u8]) -> Vec<(usize, String)> {
+    let start = &content[..content.len().min(BINARY_CHECK_LEN)];
+
+    if start.contains(&0) {
+        return Vec::new();
+    }
+
+    String::from_utf8_lossy(content)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| regex.is_match(line))
+        .map(|(i, line)| (i + 1, line.to_owned()))
+        .collect()
+}
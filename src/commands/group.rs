@@ -0,0 +1,140 @@
+use crate::{
+    commands::lookup,
+    err::{Err, Result},
+    explore, groups,
+    groups::Groups,
+    options::{GroupAddOptions, GroupCommand, GroupListOptions, GroupRemoveOptions},
+    util::PathRelativizeExtension,
+};
+
+pub fn run(command: &GroupCommand) -> Result<()> {
+    match command {
+        GroupCommand::Add(opts) => add(opts),
+        GroupCommand::Remove(opts) => remove(opts),
+        GroupCommand::List(opts) => list(opts),
+    }
+}
+
+fn add(opts: &GroupAddOptions) -> Result<()> {
+    if !groups::is_valid_name(&opts.name) {
+        return Err(Err::InvalidArgument {
+            name: "group name",
+            message: format!(
+                "{} must start with a letter and contain only letters, digits and -",
+                opts.name
+            ),
+        });
+    }
+
+    let groups = Groups::load()?;
+    let existing = groups.members(&opts.name).unwrap_or(&[]);
+    let mut added = Vec::new();
+
+    for member in &opts.members {
+        let member = if is_pattern(member) || groups.is_group(member) {
+            member.clone()
+        } else {
+            let dir = lookup::resolve_repository(&opts.base.base_dir, member, &opts.list)?;
+            relative_member(&dir, &opts.base.base_dir)
+        };
+
+        if member.eq_ignore_ascii_case(&opts.name) {
+            return Err(Err::InvalidArgument {
+                name: "MEMBER",
+                message: format!("group {} cannot contain itself", opts.name),
+            });
+        }
+
+        if !existing.contains(&member) && !added.contains(&member) {
+            added.push(member);
+        }
+    }
+
+    groups::add(&opts.name, &added)?;
+
+    for member in &added {
+        println!("Added {} to {}", member, opts.name);
+    }
+
+    Ok(())
+}
+
+fn remove(opts: &GroupRemoveOptions) -> Result<()> {
+    let groups = Groups::load()?;
+
+    let existing = groups
+        .members(&opts.name)
+        .ok_or_else(|| Err::InvalidArgument {
+            name: "group",
+            message: format!("no group named {}", opts.name),
+        })?;
+
+    let mut removed = Vec::new();
+
+    for member in &opts.members {
+        // Members are stored as given, or as the path of the repository
+        let found = existing.iter().find(|m| *m == member).cloned().or_else(|| {
+            let dir = lookup::resolve_repository(&opts.base.base_dir, member, &opts.list).ok()?;
+            let relative = relative_member(&dir, &opts.base.base_dir);
+            existing.iter().find(|m| **m == relative).cloned()
+        });
+
+        match found {
+            Some(found) => removed.push(found),
+            None => {
+                return Err(Err::InvalidArgument {
+                    name: "MEMBER",
+                    message: format!("{} is not in group {}", member, opts.name),
+                })
+            }
+        }
+    }
+
+    groups::remove(&opts.name, &removed)?;
+
+    if removed.is_empty() {
+        println!("Removed group {}", opts.name);
+    }
+
+    for member in &removed {
+        println!("Removed {} from {}", member, opts.name);
+    }
+
+    Ok(())
+}
+
+fn list(opts: &GroupListOptions) -> Result<()> {
+    let base_dir = &opts.base.base_dir;
+
+    let name = match &opts.name {
+        Some(name) => name,
+        None => {
+            for (name, members) in Groups::load()?.iter() {
+                println!("{}: {}", name, members.join(", "));
+            }
+
+            return Ok(());
+        }
+    };
+
+    let mut list = opts.list.clone();
+    list.groups.push(name.clone());
+
+    for dir in explore::find_repositories(base_dir, &list)? {
+        println!("{}", dir.normalize_relative_to(base_dir).display());
+    }
+
+    Ok(())
+}
+
+fn is_pattern(member: &str) -> bool {
+    member.contains(['*', '?'])
+}
+
+/// How a repository is stored as a member, its path relative to the base
+/// directory with `/` separators
+fn relative_member(dir: &std::path::Path, base_dir: &std::path::Path) -> String {
+    dir.normalize_relative_to(base_dir)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
@@ -1,19 +1,339 @@
-use crate::{err::Result, explore, options::ListOptions, util::PathRelativizeExtension};
-
-use std::io::{self, prelude::*};
+use crate::{
+    err::Result,
+    explore,
+    info::RepoInfo,
+    options::{Column, Format, ListOptions, SortKey},
+    remotes::RemotePreference,
+    tree::Tree,
+    util::{self, PathRelativizeExtension},
+};
+use chrono::Utc;
+use rayon::prelude::*;
+use serde_json::Value;
+use std::{
+    cmp,
+    io::{self, prelude::*},
+    path,
+};
 
 pub fn run(list_opts: &ListOptions) -> Result<()> {
-    let dirs = explore::find_git_folders(&list_opts.base.base_dir, list_opts.list.deep_recurse)?;
-    let stdin = io::stdout();
-    let mut lock = stdin.lock();
+    let dirs = explore::find_repositories(&list_opts.base.base_dir, &list_opts.list)?;
+    let stdout = io::stdout();
+    let mut lock = stdout.lock();
+
+    let terminator = if list_opts.null { '\0' } else { '\n' };
+
+    let columns = if list_opts.long {
+        Column::long()
+    } else {
+        list_opts.columns.clone()
+    };
+
+    let filtered = list_opts.since.is_some() || list_opts.stale.is_some();
+    let needs_info = !columns.is_empty()
+        || list_opts.format != Format::Text
+        || list_opts.sort.is_some()
+        || list_opts.status
+        || filtered;
+
+    if !needs_info {
+        if list_opts.tree {
+            let entries = dirs.iter().map(|d| (d.clone(), String::new()));
+            return write_tree(&mut lock, list_opts, entries);
+        }
+
+        for d in dirs {
+            write!(
+                lock,
+                "{}{}",
+                d.normalize_relative_to(&list_opts.base.base_dir).display(),
+                terminator
+            )?;
+        }
+
+        return Ok(());
+    }
+
+    let preference = RemotePreference::load(&list_opts.remote)?;
+
+    let needs_modified = columns.contains(&Column::Modified)
+        || list_opts.sort == Some(SortKey::LastModified)
+        || filtered;
+    let needs_size = columns.contains(&Column::Size) || list_opts.sort == Some(SortKey::Size);
+    let needs_dirty = columns.contains(&Column::Dirty) || (list_opts.tree && list_opts.status);
+
+    let mut infos: Vec<RepoInfo> = dirs
+        .par_iter()
+        .map(|dir| -> Result<RepoInfo> {
+            let mut info = RepoInfo::gather(dir, &preference)?;
+
+            if needs_dirty {
+                info.gather_dirty()?;
+            }
+
+            if needs_modified {
+                info.gather_last_modified()?;
+            }
+
+            if needs_size {
+                info.gather_size()?;
+            }
+
+            Ok(info)
+        })
+        .filter_map(|result| match result {
+            Ok(x) => Some(x),
+            Err(e) => {
+                eprintln!("Error received: {}", e);
+                None
+            }
+        })
+        .collect();
+
+    filter_recency(list_opts, &mut infos);
+
+    if let Some(key) = list_opts.sort {
+        sort(key, &mut infos);
+    }
+
+    if list_opts.tree {
+        let entries = infos.iter().map(|info| {
+            let label = if list_opts.status {
+                status_glyphs(info)
+            } else {
+                String::new()
+            };
+
+            (info.path.clone(), label)
+        });
+
+        return write_tree(&mut lock, list_opts, entries);
+    }
 
-    for d in dirs {
-        writeln!(
-            lock,
-            "{}",
-            d.normalize_relative_to(&list_opts.base.base_dir).display()
-        )?;
+    let rows: Vec<Vec<Value>> = infos
+        .iter()
+        .map(|info| {
+            let path = info.path.normalize_relative_to(&list_opts.base.base_dir);
+            let mut row = vec![Value::from(path.display().to_string())];
+            row.extend(columns.iter().map(|column| cell(info, *column)));
+            row
+        })
+        .collect();
+
+    let mut headers = vec!["path".to_owned()];
+    headers.extend(columns.iter().map(Column::to_string));
+
+    match list_opts.format {
+        Format::Text => write_text(&mut lock, &columns, &rows, terminator),
+        Format::Json => write_json(&mut lock, &headers, rows),
+        Format::Csv => write_delimited(&mut lock, &headers, &rows, ',', terminator, csv_escape),
+        Format::Tsv => write_delimited(&mut lock, &headers, &rows, '\t', terminator, tsv_escape),
+    }
+}
+
+/// Keep the repositories active within `--since`, or not within `--stale`
+pub fn filter_recency(list_opts: &ListOptions, infos: &mut Vec<RepoInfo>) {
+    let now = Utc::now();
+
+    if let Some(since) = list_opts.since {
+        infos.retain(|info| match info.last_activity() {
+            Some(activity) => activity >= now - since,
+            None => false,
+        });
+    }
+
+    if let Some(stale) = list_opts.stale {
+        infos.retain(|info| match info.last_activity() {
+            Some(activity) => activity < now - stale,
+            None => true,
+        });
+    }
+}
+
+/// Sort by a key. Dates and sizes are sorted largest first, with
+/// repositories missing them last.
+pub fn sort(key: SortKey, infos: &mut [RepoInfo]) {
+    match key {
+        SortKey::Path => infos.sort_by(|a, b| a.path.cmp(&b.path)),
+        SortKey::LastCommit => infos.sort_by_key(|info| cmp::Reverse(info.last_commit)),
+        SortKey::LastModified => infos.sort_by_key(|info| cmp::Reverse(info.last_modified)),
+        SortKey::Size => infos.sort_by_key(|info| cmp::Reverse(info.size)),
+        SortKey::Host => infos.sort_by_cached_key(|info| {
+            let host = info.remote_url.as_deref().and_then(util::url_host);
+            (host.is_none(), host, info.path.clone())
+        }),
+    }
+}
+
+fn status_glyphs(info: &RepoInfo) -> String {
+    let mut glyphs = Vec::new();
+
+    if info.dirty == Some(true) {
+        glyphs.push("*".to_owned());
+    }
+
+    if let Some(ahead) = info.ahead.filter(|n| *n > 0) {
+        glyphs.push(format!("↑{}", ahead));
+    }
+
+    if let Some(behind) = info.behind.filter(|n| *n > 0) {
+        glyphs.push(format!("↓{}", behind));
+    }
+
+    glyphs.join(" ")
+}
+
+fn write_tree<W, I>(out: &mut W, list_opts: &ListOptions, entries: I) -> Result<()>
+where
+    W: Write,
+    I: Iterator<Item = (path::PathBuf, String)>,
+{
+    let mut tree = Tree::default();
+
+    for (repo_path, label) in entries {
+        tree.insert(
+            &repo_path.normalize_relative_to(&list_opts.base.base_dir),
+            label,
+        );
+    }
+
+    // Only cut lines to fit when printing to a terminal
+    let width = term_size::dimensions_stdout().map(|(w, _)| w);
+
+    for line in tree.render(width) {
+        writeln!(out, "{}", line)?;
     }
 
     Ok(())
 }
+
+fn cell(info: &RepoInfo, column: Column) -> Value {
+    match column {
+        Column::Branch => info.branch.clone().into(),
+        Column::Upstream => info.upstream.clone().into(),
+        Column::Ahead => info.ahead.into(),
+        Column::Behind => info.behind.into(),
+        Column::Dirty => info.dirty.into(),
+        Column::Remote => info.remote_url.as_deref().map(util::redact_url).into(),
+        Column::Date => info.last_commit.map(|d| d.to_rfc3339()).into(),
+        Column::Author => info.author.clone().into(),
+        Column::Modified => info.last_modified.map(|d| d.to_rfc3339()).into(),
+        Column::Size => info.size.into(),
+    }
+}
+
+fn plain(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
+
+/// Human readable form of a cell, used by the text format
+fn pretty(column: Option<Column>, value: &Value) -> String {
+    match (column, value) {
+        (_, Value::Null) => "-".into(),
+        (Some(Column::Dirty), Value::Bool(true)) => "dirty".into(),
+        (Some(Column::Dirty), Value::Bool(false)) => "clean".into(),
+        (Some(Column::Ahead), v) => format!("+{}", v),
+        (Some(Column::Behind), v) => format!("-{}", v),
+        (Some(Column::Date), Value::String(s)) => s.chars().take(10).collect(),
+        (Some(Column::Modified), Value::String(s)) => s.chars().take(10).collect(),
+        (Some(Column::Size), Value::Number(n)) => util::format_size(n.as_u64().unwrap_or_default()),
+        (_, v) => plain(v),
+    }
+}
+
+fn write_text<W: Write>(
+    out: &mut W,
+    columns: &[Column],
+    rows: &[Vec<Value>],
+    terminator: char,
+) -> Result<()> {
+    let column_at = |i: usize| if i == 0 { None } else { Some(columns[i - 1]) };
+
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .enumerate()
+                .map(|(i, value)| pretty(column_at(i), value))
+                .collect()
+        })
+        .collect();
+
+    let mut widths = vec![0; columns.len() + 1];
+
+    for row in &cells {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    for row in &cells {
+        let mut line = String::new();
+
+        for (i, cell) in row.iter().enumerate() {
+            if i + 1 == row.len() {
+                line.push_str(cell);
+            } else {
+                line.push_str(&format!("{:width$}  ", cell, width = widths[i]));
+            }
+        }
+
+        write!(out, "{}{}", line, terminator)?;
+    }
+
+    Ok(())
+}
+
+pub fn write_json<W: Write>(out: &mut W, headers: &[String], rows: Vec<Vec<Value>>) -> Result<()> {
+    let objects: Vec<Value> = rows
+        .into_iter()
+        .map(|row| {
+            let object: serde_json::Map<String, Value> = headers.iter().cloned().zip(row).collect();
+
+            Value::Object(object)
+        })
+        .collect();
+
+    writeln!(out, "{}", Value::Array(objects))?;
+
+    Ok(())
+}
+
+pub fn write_delimited<W, E>(
+    out: &mut W,
+    headers: &[String],
+    rows: &[Vec<Value>],
+    delimiter: char,
+    terminator: char,
+    escape: E,
+) -> Result<()>
+where
+    W: Write,
+    E: Fn(&str) -> String,
+{
+    let header: Vec<String> = headers.iter().map(|h| escape(h)).collect();
+    write!(out, "{}{}", header.join(&delimiter.to_string()), terminator)?;
+
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|value| escape(&plain(value))).collect();
+        write!(out, "{}{}", fields.join(&delimiter.to_string()), terminator)?;
+    }
+
+    Ok(())
+}
+
+pub fn csv_escape(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+pub fn tsv_escape(field: &str) -> String {
+    field.replace(&['\t', '\n', '\r'][..], " ")
+}
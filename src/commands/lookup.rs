@@ -0,0 +1,132 @@
+use crate::{
+    err::{Err, Result},
+    explore, info,
+    options::{BaseListOptions, WhereOptions},
+    util::{self, PathRelativizeExtension},
+};
+use rayon::prelude::*;
+use std::{cmp, path};
+
+pub fn run(opts: &WhereOptions) -> Result<()> {
+    let base_dir = &opts.base.base_dir;
+
+    // A clone URL names exactly one location, so no searching is needed
+    if let Ok(dir) = util::find_dir(base_dir, &opts.query) {
+        if dir.join(".git").exists() {
+            println!("{}", dir.display());
+            return Ok(());
+        }
+    }
+
+    let dirs = explore::find_repositories(base_dir, &opts.list)?;
+
+    let mut matches: Vec<_> = dirs
+        .par_iter()
+        .filter_map(|dir| {
+            let relative = dir.normalize_relative_to(base_dir);
+            let score = match_score(&relative, &opts.query)?;
+
+            Some((score, info::recent_activity(dir), dir))
+        })
+        .collect();
+
+    matches.sort_by_key(|(score, activity, _)| cmp::Reverse((*score, *activity)));
+
+    if matches.is_empty() {
+        return Err(Err::NoMatchingRepository(opts.query.clone()));
+    }
+
+    if !opts.all {
+        matches.truncate(1);
+    }
+
+    for (_, _, dir) in matches {
+        println!("{}", dir.display());
+    }
+
+    Ok(())
+}
+
+/// How well a repository path matches a query, higher is better. `None` if
+/// it does not match at all.
+///
+/// In order, the best matches are the path ending with the query (such as
+/// `owner/repo`), the repository name being the query, the path containing
+/// the query, and the path containing the characters of the query in order.
+pub fn match_score(relative_path: &path::Path, query: &str) -> Option<u8> {
+    let path = relative_path
+        .to_string_lossy()
+        .replace('\\', "/")
+        .to_lowercase();
+    let query = query.trim_matches('/').to_lowercase();
+
+    if query.is_empty() {
+        return None;
+    }
+
+    let name = path.rsplit('/').next().unwrap_or("");
+
+    if path == query || path.ends_with(&format!("/{}", query)) {
+        if query.contains('/') {
+            Some(4)
+        } else {
+            Some(3)
+        }
+    } else if name.contains(&query) {
+        Some(2)
+    } else if path.contains(&query) {
+        Some(1)
+    } else if is_subsequence(&query, &path) {
+        Some(0)
+    } else {
+        None
+    }
+}
+
+/// The one repository named by `repo`, for commands that change it. Unlike
+/// `where`, nothing is guessed: `repo` must be a clone URL, a path relative
+/// to the base directory or the working directory, or an `owner/repo` suffix
+/// that only one repository has. Groups selected with `--group` only narrow
+/// the suffix search.
+pub fn resolve_repository(
+    base_dir: &path::Path,
+    repo: &str,
+    list: &BaseListOptions,
+) -> Result<path::PathBuf> {
+    let candidates = util::find_dir(base_dir, repo)
+        .into_iter()
+        .chain(vec![base_dir.join(repo), path::PathBuf::from(repo)]);
+
+    // Paths are canonicalized so that `..` or a symbolic link cannot name a
+    // repository outside the base directory
+    for dir in candidates {
+        if let Ok(dir) = dir.canonicalize() {
+            if dir != base_dir && dir.starts_with(base_dir) && dir.join(".git").exists() {
+                return Ok(dir);
+            }
+        }
+    }
+
+    let mut matches: Vec<path::PathBuf> = explore::find_repositories(base_dir, list)?
+        .into_iter()
+        .filter(|dir| match_score(&dir.normalize_relative_to(base_dir), repo) == Some(4))
+        .collect();
+
+    match matches.len() {
+        0 => Err(Err::NoMatchingRepository(repo.into())),
+        1 => Ok(matches.remove(0)),
+        _ => Err(Err::AmbiguousRepository {
+            query: repo.into(),
+            matches: matches
+                .iter()
+                .map(|dir| dir.normalize_relative_to(base_dir))
+                .collect(),
+        }),
+    }
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
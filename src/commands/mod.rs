@@ -1,5 +1,19 @@
 pub mod check;
 pub mod clone;
+pub mod complete;
+pub mod duplicates;
 pub mod gen_completions;
+pub mod gen_docs;
+pub mod grep;
+pub mod group;
 pub mod list;
+pub mod lookup;
+pub mod mv;
 pub mod organize;
+pub mod prune_branches;
+pub mod remote_rewrite;
+pub mod rm;
+pub mod shell_init;
+pub mod standup;
+pub mod stats;
+pub mod switch;
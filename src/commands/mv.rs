@@ -0,0 +1,195 @@
+use crate::{
+    commands::{lookup, rm},
+    err::{Err, Result},
+    options::MvOptions,
+    remotes::RemotePreference,
+    util::{self, PathRelativizeExtension},
+};
+use std::{fs, path};
+
+const GITDIR_PREFIX: &str = "gitdir: ";
+
+pub fn run(opts: &MvOptions) -> Result<()> {
+    let base_dir = &opts.base.base_dir;
+    let from = lookup::resolve_repository(base_dir, &opts.repo, &opts.list)?;
+
+    // A clone URL gives the new location the same way `clone` would
+    let new_url = util::find_dir(base_dir, &opts.destination)
+        .ok()
+        .map(|to| (to, &opts.destination));
+
+    let to = match &new_url {
+        Some((to, _)) => to.clone(),
+        None => base_dir.join(&opts.destination),
+    };
+
+    // `..` would leave the base directory without `starts_with` noticing
+    let inside_base = to
+        .strip_prefix(base_dir)
+        .map(|rest| {
+            rest.components().next().is_some()
+                && rest
+                    .components()
+                    .all(|c| matches!(c, path::Component::Normal(_)))
+        })
+        .unwrap_or(false);
+
+    if !inside_base {
+        return Err(Err::InvalidArgument {
+            name: "destination",
+            message: format!("{} is not inside the base directory", to.display()),
+        });
+    }
+
+    if to.exists() && to != from {
+        return Err(Err::InvalidArgument {
+            name: "destination",
+            message: format!("{} already exists", to.display()),
+        });
+    }
+
+    let prefix = if opts.dry_run { "Would move" } else { "Moved" };
+
+    let mut new_remote_url = None;
+
+    if let Some((_, url)) = new_url.filter(|_| !opts.keep_url) {
+        let repo = git2::Repository::open(&from)?;
+        let preference = RemotePreference::load(&opts.remote)?;
+
+        if let Some(remote) = preference.canonical_remote(&repo)? {
+            if remote.url != *url {
+                new_remote_url = Some((remote.name, url));
+            }
+        }
+    }
+
+    if to != from {
+        if !opts.dry_run {
+            relocate(&from, &to, base_dir)?;
+        }
+
+        println!(
+            "{} {} to {}",
+            prefix,
+            from.normalize_relative_to(base_dir).display(),
+            to.normalize_relative_to(base_dir).display()
+        );
+    }
+
+    // The url only changes once the repository is where it says it should be
+    if let Some((name, url)) = new_remote_url {
+        if !opts.dry_run {
+            git2::Repository::open(&to)?.remote_set_url(&name, url)?;
+        }
+
+        let verb = if opts.dry_run { "Would set" } else { "Set" };
+        println!("{} {} url to {}", verb, name, util::redact_url(url));
+    }
+
+    Ok(())
+}
+
+/// Move a repository within the base directory, repairing its worktree links
+/// and removing parent directories left empty
+pub fn relocate(from: &path::Path, to: &path::Path, base_dir: &path::Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::rename(from, to)?;
+
+    for repaired in repair_worktrees(from, to)? {
+        println!("Repaired worktree link {}", repaired.display());
+    }
+
+    rm::remove_empty_parents(from, base_dir);
+
+    Ok(())
+}
+
+/// Fix the files that link a repository and its worktrees by absolute path
+/// after the repository moved from `from` to `to`, returning the files that
+/// changed.
+///
+/// A main repository lists each linked worktree in
+/// `.git/worktrees/<name>/gitdir`, and each worktree has a `.git` file
+/// pointing back there. If the moved repository is itself a linked worktree,
+/// its `.git` file leads to the `gitdir` file in the main repository.
+pub fn repair_worktrees(from: &path::Path, to: &path::Path) -> Result<Vec<path::PathBuf>> {
+    let mut repaired = Vec::new();
+    let dot_git = to.join(".git");
+
+    if dot_git.is_file() {
+        if let Some(admin_dir) = read_link(&dot_git)? {
+            let gitdir = admin_dir.join("gitdir");
+
+            if gitdir.is_file() && relink(&gitdir, from, to, "")? {
+                repaired.push(gitdir);
+            }
+        }
+
+        return Ok(repaired);
+    }
+
+    let worktrees = dot_git.join("worktrees");
+
+    if !worktrees.is_dir() {
+        return Ok(repaired);
+    }
+
+    for entry in fs::read_dir(worktrees)? {
+        let gitdir = entry?.path().join("gitdir");
+
+        if !gitdir.is_file() {
+            continue;
+        }
+
+        // A worktree inside the moved directory moved along with it
+        if relink(&gitdir, from, to, "")? {
+            repaired.push(gitdir.clone());
+        }
+
+        if let Some(worktree_link) = read_link(&gitdir)? {
+            if worktree_link.is_file() && relink(&worktree_link, from, to, GITDIR_PREFIX)? {
+                repaired.push(worktree_link);
+            }
+        }
+    }
+
+    Ok(repaired)
+}
+
+/// The path a link file holds, either bare or after `gitdir: `. Relative
+/// links are relative to the directory holding the file.
+fn read_link(file: &path::Path) -> Result<Option<path::PathBuf>> {
+    let content = fs::read_to_string(file)?;
+    let content = content.trim_end();
+
+    let link = content.strip_prefix(GITDIR_PREFIX).unwrap_or(content);
+
+    if link.is_empty() {
+        return Ok(None);
+    }
+
+    match file.parent() {
+        Some(dir) => Ok(Some(dir.join(link))),
+        None => Ok(Some(link.into())),
+    }
+}
+
+/// Point a link file at `to` instead of `from`, returning whether it changed
+pub fn relink(file: &path::Path, from: &path::Path, to: &path::Path, prefix: &str) -> Result<bool> {
+    let link = match read_link(file)? {
+        Some(link) => link,
+        None => return Ok(false),
+    };
+
+    let rest = match link.strip_prefix(from) {
+        Ok(rest) => rest,
+        Err(_) => return Ok(false),
+    };
+
+    fs::write(file, format!("{}{}\n", prefix, to.join(rest).display()))?;
+
+    Ok(true)
+}
@@ -1,72 +1,268 @@
 use crate::{
-    err::Result,
+    commands::check,
+    err::{Err, Result},
     explore,
     options::OrganizeOptions,
+    remotes::RemotePreference,
     util::{self, PathRelativizeExtension},
 };
-use std::fs;
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, prelude::*},
+    path,
+};
 
-pub fn run(opts: &OrganizeOptions) -> Result<()> {
-    let dirs = explore::find_git_folders(&opts.dir, false)?;
+pub struct Move {
+    pub from: path::PathBuf,
+    pub to: path::PathBuf,
+    pub remote: Option<String>,
+    pub warnings: Vec<check::Warning>,
+}
 
-    let mut count = 0;
+enum Answer {
+    Accept,
+    Skip,
+    Edit,
+    Quit,
+}
 
-    for dir in dirs {
-        let git_path = dir.join(".git");
+pub fn run(opts: &OrganizeOptions) -> Result<()> {
+    let mut plan = plan_moves(opts)?;
 
-        let repo = git2::Repository::open(git_path)?;
+    if opts.interactive {
+        let stdin = io::stdin();
 
-        let new_dir = match canonical_url(&repo)? {
-            Some(remote_url) => util::find_dir(&opts.new_dir, &remote_url)?,
-            None => "no-remote".into(),
+        plan = match review(opts, plan, &mut stdin.lock())? {
+            Some(approved) => approved,
+            None => {
+                println!("Quit without moving anything");
+                return Ok(());
+            }
         };
+    }
 
-        println!(
-            "{} -> {}",
-            dir.normalize_relative_to(&opts.dir).display(),
-            new_dir.normalize_relative_to(&opts.new_dir).display()
-        );
+    // Nothing is moved unless every move can be made
+    let conflicts = conflicts(opts, &plan);
 
-        if !opts.dry_run {
-            fs::create_dir_all(new_dir.parent().unwrap())?;
+    if !conflicts.is_empty() {
+        for conflict in &conflicts {
+            eprintln!("{}", conflict);
+        }
+
+        return Err(Err::InvalidArgument {
+            name: "organization",
+            message: format!("{} moves conflict, nothing was moved", conflicts.len()),
+        });
+    }
 
-            fs::rename(dir, new_dir)?;
+    for m in &plan {
+        if !opts.interactive {
+            println!(
+                "{} -> {}",
+                m.from.normalize_relative_to(&opts.dir).display(),
+                m.to.normalize_relative_to(&opts.new_dir).display()
+            );
         }
 
-        count += 1;
+        if !opts.dry_run {
+            fs::create_dir_all(m.to.parent().unwrap())?;
+
+            fs::rename(&m.from, &m.to)?;
+        }
     }
 
     if opts.dry_run {
-        println!("Will move {} directories", count);
+        println!("Will move {} directories", plan.len());
     } else {
-        println!("Moved {} directories", count);
+        println!("Moved {} directories", plan.len());
     }
 
     Ok(())
 }
 
-fn canonical_url(repo: &git2::Repository) -> Result<Option<String>> {
-    if let Ok(remote) = repo.find_remote("origin") {
-        if let Some(url) = remote.url() {
-            return Ok(Some(url.into()));
+pub fn plan_moves(opts: &OrganizeOptions) -> Result<Vec<Move>> {
+    let dirs = explore::find_git_folders(&opts.dir, false)?;
+    let preference = RemotePreference::load(&opts.remote)?;
+
+    let mut plan = Vec::new();
+
+    for dir in dirs {
+        let git_path = dir.join(".git");
+
+        let repo = git2::Repository::open(git_path)?;
+
+        let (new_dir, remote) = match preference.canonical_remote(&repo)? {
+            Some(remote) => (
+                util::find_dir(&opts.new_dir, &remote.url)?,
+                Some(remote.name),
+            ),
+            None => (
+                opts.new_dir
+                    .join("no-remote")
+                    .join(dir.normalize_relative_to(&opts.dir)),
+                None,
+            ),
+        };
+
+        // Only gather status when it will be shown, since it is comparatively slow
+        let warnings = if opts.interactive {
+            check::work_warnings(&repo, &Default::default())?
+        } else {
+            Vec::new()
+        };
+
+        plan.push(Move {
+            from: dir,
+            to: new_dir,
+            remote,
+            warnings,
+        });
+    }
+
+    Ok(plan)
+}
+
+/// The problems that would stop some moves of a plan: destinations that
+/// already exist, and destinations that several repositories would move to
+pub fn conflicts(opts: &OrganizeOptions, plan: &[Move]) -> Vec<String> {
+    let mut conflicts = Vec::new();
+    let mut sources: HashMap<&path::Path, &path::Path> = HashMap::new();
+
+    for m in plan.iter().filter(|m| m.to != m.from) {
+        let from = m.from.normalize_relative_to(&opts.dir);
+        let to = m.to.normalize_relative_to(&opts.new_dir);
+
+        if let Some(other) = sources.insert(&m.to, &m.from) {
+            conflicts.push(format!(
+                "{} and {} would both move to {}",
+                other.normalize_relative_to(&opts.dir).display(),
+                from.display(),
+                to.display()
+            ));
+        } else if m.to.exists() {
+            conflicts.push(format!(
+                "{} cannot move to {}, which already exists",
+                from.display(),
+                to.display()
+            ));
         }
     }
 
-    first_remote_with_url(repo)
+    conflicts
 }
 
-fn first_remote_with_url(repo: &git2::Repository) -> Result<Option<String>> {
-    let remotes = repo.remotes()?;
+/// A destination typed in while reviewing, which has to stay inside NEW_DIR
+/// the way `mv` stays inside the base directory
+fn edited_destination(opts: &OrganizeOptions, dest: &str) -> Result<path::PathBuf> {
+    let relative = path::Path::new(dest);
+
+    if !relative
+        .components()
+        .all(|c| matches!(c, path::Component::Normal(_)))
+    {
+        return Err(Err::InvalidArgument {
+            name: "destination",
+            message: format!("{} is not inside {}", dest, opts.new_dir.display()),
+        });
+    }
+
+    Ok(opts.new_dir.join(relative))
+}
+
+/// Ask about each planned move, reading the answers from `input`. Returns
+/// `None` if the user quit.
+pub fn review<R: BufRead>(
+    opts: &OrganizeOptions,
+    plan: Vec<Move>,
+    input: &mut R,
+) -> Result<Option<Vec<Move>>> {
+    let mut approved = Vec::new();
+
+    for mut m in plan {
+        println!(
+            "{} -> {}",
+            m.from.normalize_relative_to(&opts.dir).display(),
+            m.to.normalize_relative_to(&opts.new_dir).display()
+        );
+
+        match &m.remote {
+            Some(remote) => println!("  remote: {}", remote),
+            None => println!("  remote: none"),
+        }
+
+        for warning in &m.warnings {
+            println!("  - {}", warning);
+        }
 
-    for remote_name in remotes.iter() {
-        if let Some(remote_name) = remote_name {
-            if let Ok(remote) = repo.find_remote(remote_name) {
-                if let Some(url) = remote.url() {
-                    return Ok(Some(url.into()));
+        loop {
+            match prompt_answer(input)? {
+                Answer::Accept => {
+                    approved.push(m);
+                    break;
                 }
+                Answer::Skip => break,
+                Answer::Edit => {
+                    let new_dest = match prompt("New destination (relative to NEW_DIR): ", input)? {
+                        Some(dest) => dest,
+                        None => return Ok(None),
+                    };
+
+                    if new_dest.is_empty() {
+                        continue;
+                    }
+
+                    match edited_destination(opts, &new_dest) {
+                        Ok(to) => {
+                            m.to = to;
+                            println!(
+                                "{} -> {}",
+                                m.from.normalize_relative_to(&opts.dir).display(),
+                                m.to.normalize_relative_to(&opts.new_dir).display()
+                            );
+                        }
+                        Err(e) => println!("  {}", e),
+                    }
+                }
+                Answer::Quit => return Ok(None),
             }
         }
+
+        println!();
+    }
+
+    Ok(Some(approved))
+}
+
+fn prompt_answer<R: BufRead>(input: &mut R) -> Result<Answer> {
+    loop {
+        let answer = match prompt("Move? [y]es, [n]o, [e]dit destination, [q]uit: ", input)? {
+            Some(answer) => answer,
+            None => return Ok(Answer::Quit),
+        };
+
+        match answer.to_lowercase().as_str() {
+            "y" | "yes" => return Ok(Answer::Accept),
+            "n" | "no" => return Ok(Answer::Skip),
+            "e" | "edit" => return Ok(Answer::Edit),
+            "q" | "quit" => return Ok(Answer::Quit),
+            _ => continue,
+        }
+    }
+}
+
+/// Returns `None` at the end of input
+fn prompt<R: BufRead>(message: &str, input: &mut R) -> Result<Option<String>> {
+    let stdout = io::stdout();
+    let mut lock = stdout.lock();
+    write!(lock, "{}", message)?;
+    lock.flush()?;
+
+    let mut line = String::new();
+
+    if input.read_line(&mut line)? == 0 {
+        return Ok(None);
     }
 
-    Ok(None)
+    Ok(Some(line.trim().into()))
 }
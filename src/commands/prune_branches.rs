@@ -0,0 +1,249 @@
+use crate::{
+    commands::check, err::Result, explore, options::PruneBranchesOptions,
+    remotes::RemotePreference, util::PathRelativizeExtension,
+};
+use rayon::prelude::*;
+use std::path;
+
+struct Repository {
+    path: String,
+    default_branch: String,
+    branches: Vec<Branch>,
+}
+
+struct Branch {
+    name: String,
+    reason: Reason,
+    deleted: bool,
+    /// Why deleting the branch failed
+    error: Option<git2::Error>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Reason {
+    Merged,
+    UpstreamGone(String),
+    /// The upstream is gone, but some commits are on no remote
+    Unpushed(String, usize),
+}
+
+impl Reason {
+    fn prunable(&self) -> bool {
+        match self {
+            Reason::Merged | Reason::UpstreamGone(_) => true,
+            Reason::Unpushed(..) => false,
+        }
+    }
+}
+
+pub fn run(opts: &PruneBranchesOptions) -> Result<()> {
+    let base_dir = &opts.base.base_dir;
+    let paths = explore::find_repositories(base_dir, &opts.list)?;
+    let preference = RemotePreference::load(&opts.remote)?;
+
+    let mut repos: Vec<Repository> = paths
+        .par_iter()
+        .map(|dir| prune_repository(dir, base_dir, &preference, opts.apply))
+        .filter_map(|result| match result {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("Error received: {}", e);
+                None
+            }
+        })
+        .filter(|repo| !repo.branches.is_empty())
+        .collect();
+
+    repos.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut pruned = 0;
+    let mut pruned_repos = 0;
+
+    for repo in &repos {
+        println!("{} (default branch {})", repo.path, repo.default_branch);
+
+        for branch in &repo.branches {
+            let action = match (branch.reason.prunable(), opts.apply) {
+                (true, true) if branch.deleted => "deleted",
+                (true, true) => "failed to delete",
+                (true, false) => "would delete",
+                (false, _) => "keeping",
+            };
+
+            let reason = match &branch.reason {
+                Reason::Merged => format!("merged into {}", repo.default_branch),
+                Reason::UpstreamGone(upstream) => format!("upstream {} is gone", upstream),
+                Reason::Unpushed(upstream, commits) => format!(
+                    "upstream {} is gone, but {} commits are not on any remote",
+                    upstream, commits
+                ),
+            };
+
+            match &branch.error {
+                Some(e) => println!("  - {} {}: {} ({})", action, branch.name, reason, e),
+                None => println!("  - {} {}: {}", action, branch.name, reason),
+            }
+        }
+
+        println!();
+
+        let count = repo
+            .branches
+            .iter()
+            .filter(|b| b.reason.prunable() && (b.deleted || !opts.apply))
+            .count();
+
+        if count > 0 {
+            pruned += count;
+            pruned_repos += 1;
+        }
+    }
+
+    if opts.apply {
+        println!(
+            "Deleted {} branches in {} repositories",
+            pruned, pruned_repos
+        );
+    } else {
+        println!(
+            "Would delete {} branches in {} repositories. Run with --apply to delete them",
+            pruned, pruned_repos
+        );
+    }
+
+    Ok(())
+}
+
+/// Find the branches of one repository that can be pruned, deleting them if
+/// `apply` is set. Repositories without a known default branch are skipped.
+fn prune_repository(
+    path: &path::Path,
+    base_dir: &path::Path,
+    preference: &RemotePreference,
+    apply: bool,
+) -> Result<Option<Repository>> {
+    let repo = git2::Repository::open(path)?;
+
+    let remote = match preference.canonical_remote(&repo)? {
+        Some(remote) => remote,
+        None => return Ok(None),
+    };
+
+    let (default_branch, default_tip) = match default_branch(&repo, &remote.name) {
+        Some(default) => default,
+        None => return Ok(None),
+    };
+
+    let default_name = default_branch[remote.name.len() + 1..].to_owned();
+
+    let config = repo.config()?;
+    let mut branches = Vec::new();
+
+    for mut branch in check::strip_branch_errors(repo.branches(Some(git2::BranchType::Local))?) {
+        let name = match branch.name() {
+            Ok(Some(name)) => name.to_owned(),
+            _ => continue,
+        };
+
+        if branch.is_head() || name == default_name {
+            continue;
+        }
+
+        let reason = match classify(&repo, &config, &branch, &name, default_tip)? {
+            Some(reason) => reason,
+            None => continue,
+        };
+
+        let error = if apply && reason.prunable() {
+            branch.delete().err()
+        } else {
+            None
+        };
+
+        branches.push(Branch {
+            name,
+            deleted: apply && reason.prunable() && error.is_none(),
+            reason,
+            error,
+        });
+    }
+
+    Ok(Some(Repository {
+        path: format!("{}", path.normalize_relative_to(base_dir).display()),
+        default_branch,
+        branches,
+    }))
+}
+
+/// Why a local branch could be pruned, or `None` to leave it out. A branch
+/// at the same commit as the default branch has no work of its own to be
+/// merged, such as one that was just created, and is only pruned once its
+/// upstream is gone.
+pub fn classify(
+    repo: &git2::Repository,
+    config: &git2::Config,
+    branch: &git2::Branch,
+    name: &str,
+    default_tip: git2::Oid,
+) -> Result<Option<Reason>> {
+    let tip = match branch.get().target() {
+        Some(tip) => tip,
+        None => return Ok(None),
+    };
+
+    if tip != default_tip && repo.graph_descendant_of(default_tip, tip)? {
+        return Ok(Some(Reason::Merged));
+    }
+
+    let upstream = match check::configured_upstream(config, name) {
+        Some(upstream) => upstream,
+        None => return Ok(None),
+    };
+
+    if branch.upstream().is_ok() {
+        return Ok(None);
+    }
+
+    match commits_not_on_remote(repo, tip)? {
+        0 => Ok(Some(Reason::UpstreamGone(upstream))),
+        commits => Ok(Some(Reason::Unpushed(upstream, commits))),
+    }
+}
+
+/// The default branch of a remote, such as `origin/main`, and its commit.
+/// This is where `refs/remotes/<remote>/HEAD` points, falling back to `main`
+/// or `master` when the remote's HEAD was never fetched.
+pub fn default_branch(repo: &git2::Repository, remote: &str) -> Option<(String, git2::Oid)> {
+    let head = format!("refs/remotes/{}/HEAD", remote);
+
+    let mut candidates = Vec::new();
+
+    if let Ok(reference) = repo.find_reference(&head) {
+        if let Some(target) = reference.symbolic_target() {
+            candidates.push(target.to_owned());
+        }
+    }
+
+    candidates.push(format!("refs/remotes/{}/main", remote));
+    candidates.push(format!("refs/remotes/{}/master", remote));
+
+    candidates.into_iter().find_map(|name| {
+        let oid = repo.refname_to_id(&name).ok()?;
+        Some((name.trim_start_matches("refs/remotes/").to_owned(), oid))
+    })
+}
+
+/// Number of commits reachable from `tip` that no remote-tracking branch
+/// contains
+fn commits_not_on_remote(repo: &git2::Repository, tip: git2::Oid) -> Result<usize> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(tip)?;
+
+    for branch in check::strip_branch_errors(repo.branches(Some(git2::BranchType::Remote))?) {
+        if let Some(remote_tip) = branch.get().target() {
+            revwalk.hide(remote_tip)?;
+        }
+    }
+
+    Ok(revwalk.count())
+}
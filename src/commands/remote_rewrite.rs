@@ -0,0 +1,257 @@
+use crate::{
+    commands::mv,
+    err::{Err, Result},
+    explore,
+    options::{Protocol, RemoteRewriteOptions},
+    remotes::RemotePreference,
+    util::{self, PathRelativizeExtension},
+};
+use rayon::prelude::*;
+use regex::Regex;
+use std::path;
+
+/// How to rewrite remote URLs
+pub struct UrlRewrite {
+    pattern: Option<Pattern>,
+    replacement: String,
+    protocol: Option<Protocol>,
+}
+
+enum Pattern {
+    Prefix(String),
+    Regex(Regex),
+}
+
+struct Repository {
+    path: path::PathBuf,
+    changes: Vec<Change>,
+    /// Matching URLs that could not be rewritten, and why
+    failures: Vec<(String, Err)>,
+    /// Where the repository belongs after its URLs are rewritten
+    destination: Option<path::PathBuf>,
+}
+
+struct Change {
+    remote: String,
+    push: bool,
+    old: String,
+    new: String,
+}
+
+impl UrlRewrite {
+    pub fn new(
+        from: Option<&str>,
+        to: Option<&str>,
+        regex: bool,
+        protocol: Option<Protocol>,
+    ) -> Result<UrlRewrite> {
+        let pattern = match from {
+            Some(from) if regex => Some(Pattern::Regex(Regex::new(from).map_err(|e| {
+                Err::InvalidArgument {
+                    name: "--from",
+                    message: e.to_string(),
+                }
+            })?)),
+            Some(from) => Some(Pattern::Prefix(from.into())),
+            None => None,
+        };
+
+        Ok(UrlRewrite {
+            pattern,
+            replacement: to.unwrap_or("").into(),
+            protocol,
+        })
+    }
+
+    /// The rewritten URL, or `None` if the URL does not match or would not
+    /// change. A matching URL that cannot be converted to the protocol is an
+    /// error.
+    pub fn apply(&self, url: &str) -> Result<Option<String>> {
+        let mut new = match &self.pattern {
+            Some(Pattern::Prefix(prefix)) if url.starts_with(prefix.as_str()) => {
+                format!("{}{}", self.replacement, &url[prefix.len()..])
+            }
+            Some(Pattern::Regex(regex)) if regex.is_match(url) => {
+                regex.replace(url, self.replacement.as_str()).into_owned()
+            }
+            Some(_) => return Ok(None),
+            None => url.to_owned(),
+        };
+
+        if let Some(protocol) = self.protocol {
+            let converted = match protocol {
+                Protocol::Ssh => util::ssh_url(&new),
+                Protocol::Https => util::https_url(&new),
+            };
+
+            new = converted.ok_or_else(|| Err::UnconvertibleUrl {
+                url: util::redact_url(&new),
+                protocol,
+            })?;
+        }
+
+        if new == url {
+            Ok(None)
+        } else {
+            Ok(Some(new))
+        }
+    }
+}
+
+pub fn run(opts: &RemoteRewriteOptions) -> Result<()> {
+    let base_dir = &opts.base.base_dir;
+    let paths = explore::find_repositories(base_dir, &opts.list)?;
+    let preference = RemotePreference::load(&opts.remote)?;
+    let rewrite = UrlRewrite::new(
+        opts.from.as_deref(),
+        opts.to.as_deref(),
+        opts.regex,
+        opts.protocol,
+    )?;
+
+    let mut repos: Vec<Repository> = paths
+        .par_iter()
+        .map(|dir| plan(dir, base_dir, &rewrite, &preference))
+        .filter_map(|result| match result {
+            Ok(x) => Some(x),
+            Err(e) => {
+                eprintln!("Error received: {}", e);
+                None
+            }
+        })
+        .filter(|repo| !repo.changes.is_empty() || !repo.failures.is_empty())
+        .collect();
+
+    repos.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut rewritten = 0;
+    let mut failed = 0;
+
+    for repo in &repos {
+        println!("{}", repo.path.normalize_relative_to(base_dir).display());
+
+        for (remote, e) in &repo.failures {
+            println!("  - {}: not rewritten, {}", remote, e);
+        }
+
+        failed += repo.failures.len();
+
+        if let Err(e) = apply(repo, base_dir, opts) {
+            eprintln!("Error received: {}", e);
+        } else {
+            rewritten += repo.changes.len();
+        }
+
+        println!();
+    }
+
+    let verb = if opts.dry_run {
+        "Would rewrite"
+    } else {
+        "Rewrote"
+    };
+    println!(
+        "{} {} remote URLs in {} repositories",
+        verb,
+        rewritten,
+        repos.len()
+    );
+
+    if failed > 0 {
+        println!("{} matching remote URLs could not be rewritten", failed);
+    }
+
+    Ok(())
+}
+
+fn plan(
+    dir: &path::Path,
+    base_dir: &path::Path,
+    rewrite: &UrlRewrite,
+    preference: &RemotePreference,
+) -> Result<Repository> {
+    let repo = git2::Repository::open(dir)?;
+    let mut changes = Vec::new();
+    let mut failures = Vec::new();
+
+    for name in repo.remotes()?.iter().flatten() {
+        let remote = repo.find_remote(name)?;
+
+        for (push, url) in [(false, remote.url()), (true, remote.pushurl())] {
+            let url = match url {
+                Some(url) => url,
+                None => continue,
+            };
+
+            match rewrite.apply(url) {
+                Ok(Some(new)) => changes.push(Change {
+                    remote: name.to_owned(),
+                    push,
+                    old: url.to_owned(),
+                    new,
+                }),
+                Ok(None) => {}
+                Err(e) => failures.push((name.to_owned(), e)),
+            }
+        }
+    }
+
+    // Only a repository whose own location changes is moved, not one that
+    // was already somewhere else
+    let destination = match preference.canonical_remote(&repo)? {
+        Some(canonical) => changes
+            .iter()
+            .find(|c| c.remote == canonical.name && !c.push)
+            .and_then(|c| util::find_dir(base_dir, &c.new).ok())
+            .filter(|to| to != dir),
+        None => None,
+    };
+
+    Ok(Repository {
+        path: dir.to_path_buf(),
+        changes,
+        failures,
+        destination,
+    })
+}
+
+fn apply(repo: &Repository, base_dir: &path::Path, opts: &RemoteRewriteOptions) -> Result<()> {
+    let git_repo = git2::Repository::open(&repo.path)?;
+
+    for change in &repo.changes {
+        if !opts.dry_run {
+            if change.push {
+                git_repo.remote_set_pushurl(&change.remote, Some(&change.new))?;
+            } else {
+                git_repo.remote_set_url(&change.remote, &change.new)?;
+            }
+        }
+
+        let push = if change.push { " (push)" } else { "" };
+        println!(
+            "  - {}{}: {} -> {}",
+            change.remote,
+            push,
+            util::redact_url(&change.old),
+            util::redact_url(&change.new)
+        );
+    }
+
+    let to = match &repo.destination {
+        Some(to) if opts.relocate => to,
+        _ => return Ok(()),
+    };
+
+    let relative = to.normalize_relative_to(base_dir);
+
+    if to.exists() {
+        println!("  - not moved, {} already exists", relative.display());
+    } else if opts.dry_run {
+        println!("  - would move to {}", relative.display());
+    } else {
+        mv::relocate(&repo.path, to, base_dir)?;
+        println!("  - moved to {}", relative.display());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,217 @@
+use crate::{
+    commands::{check, lookup},
+    err::{Err, Result},
+    options::RmOptions,
+    util::PathRelativizeExtension,
+};
+use std::{env, fs, path, process};
+
+pub fn run(opts: &RmOptions) -> Result<()> {
+    let base_dir = &opts.base.base_dir;
+    let dir = lookup::resolve_repository(base_dir, &opts.repo, &opts.list)?;
+    let relative = dir.normalize_relative_to(base_dir);
+
+    let repo = git2::Repository::open(&dir)?;
+    // Deleting is final, so the remotes are asked which tags they have
+    let work_opts = check::WorkOptions {
+        fetch: true,
+        ..Default::default()
+    };
+    let warnings = check::work_warnings(&repo, &work_opts)?;
+
+    if !warnings.is_empty() {
+        println!("{}", relative.display());
+
+        for warning in &warnings {
+            println!("  - {}", warning);
+        }
+
+        println!();
+
+        let unreachable: Vec<String> = warnings
+            .iter()
+            .filter_map(|warning| match warning {
+                check::Warning::TagsUnknown { remote } => Some(remote.clone()),
+                _ => None,
+            })
+            .collect();
+
+        if !opts.force && !unreachable.is_empty() {
+            return Err(Err::UnverifiedTags {
+                path: relative,
+                remotes: unreachable,
+            });
+        }
+
+        if !opts.force {
+            return Err(Err::UnpushedWork(relative));
+        }
+    }
+
+    if let Some(bundle) = &opts.bundle {
+        let bundle = env::current_dir()?.join(bundle);
+
+        if bundle.starts_with(&dir) {
+            return Err(Err::InvalidArgument {
+                name: "--bundle",
+                message: "the bundle cannot be inside the repository being removed".into(),
+            });
+        }
+
+        let tags = unpushed_tags(&repo, &warnings)?;
+
+        if unpushed_commits(&repo)? == 0 && tags.is_empty() {
+            println!("Nothing unpushed to bundle");
+        } else {
+            create_bundle(&repo, &dir, &bundle, &tags)?;
+            println!("Saved unpushed refs to {}", bundle.display());
+        }
+    }
+
+    drop(repo);
+    fs::remove_dir_all(&dir)?;
+    remove_empty_parents(&dir, base_dir);
+
+    println!("Removed {}", relative.display());
+
+    Ok(())
+}
+
+/// Local refs that may hold commits no remote has
+fn local_refs(repo: &git2::Repository) -> Result<Vec<(String, git2::Oid)>> {
+    let mut refs = Vec::new();
+
+    for reference in repo.references()? {
+        let reference = reference?;
+
+        let name = match reference.name() {
+            Some(name) => name,
+            None => continue,
+        };
+
+        let local = name.starts_with("refs/heads/")
+            || name.starts_with("refs/tags/")
+            || name == "refs/stash";
+
+        if local {
+            if let Ok(commit) = reference.peel_to_commit() {
+                refs.push((name.to_owned(), commit.id()));
+            }
+        }
+    }
+
+    Ok(refs)
+}
+
+/// Number of commits reachable from local refs that no remote-tracking
+/// branch contains
+pub fn unpushed_commits(repo: &git2::Repository) -> Result<usize> {
+    let mut revwalk = repo.revwalk()?;
+
+    for (_, oid) in local_refs(repo)? {
+        revwalk.push(oid)?;
+    }
+
+    for branch in check::strip_branch_errors(repo.branches(Some(git2::BranchType::Remote))?) {
+        if let Some(tip) = branch.get().target() {
+            revwalk.hide(tip)?;
+        }
+    }
+
+    Ok(revwalk.count())
+}
+
+/// The refs of the tags no remote has. When a remote could not be asked,
+/// every tag is taken as unpushed.
+pub fn unpushed_tags(repo: &git2::Repository, warnings: &[check::Warning]) -> Result<Vec<String>> {
+    let unknown = warnings
+        .iter()
+        .any(|warning| matches!(warning, check::Warning::TagsUnknown { .. }));
+
+    if unknown {
+        return Ok(local_refs(repo)?
+            .into_iter()
+            .map(|(name, _)| name)
+            .filter(|name| name.starts_with("refs/tags/"))
+            .collect());
+    }
+
+    Ok(warnings
+        .iter()
+        .filter_map(|warning| match warning {
+            check::Warning::UnpushedTag { tag, .. } => Some(format!("refs/tags/{}", tag)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Whether a commit is on a remote-tracking branch
+fn on_remote_branch(repo: &git2::Repository, oid: git2::Oid) -> Result<bool> {
+    for branch in check::strip_branch_errors(repo.branches(Some(git2::BranchType::Remote))?) {
+        if let Some(tip) = branch.get().target() {
+            if tip == oid || repo.graph_descendant_of(tip, oid)? {
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Bundle every local branch, the stash and the given tags, leaving out
+/// commits that are on a remote-tracking branch. git drops refs whose commit
+/// is left out, so when a tag points to a pushed commit nothing is left out.
+fn create_bundle(
+    repo: &git2::Repository,
+    dir: &path::Path,
+    bundle: &path::Path,
+    tags: &[String],
+) -> Result<()> {
+    let mut refs: Vec<(String, git2::Oid)> = local_refs(repo)?
+        .into_iter()
+        .filter(|(name, _)| !name.starts_with("refs/tags/"))
+        .collect();
+
+    let mut pushed_tag = false;
+
+    for tag in tags {
+        let commit = repo.revparse_single(tag)?.peel_to_commit()?;
+        pushed_tag |= on_remote_branch(repo, commit.id())?;
+        refs.push((tag.clone(), commit.id()));
+    }
+
+    let mut command = process::Command::new("git");
+    command
+        .args(["bundle", "create"])
+        .arg(bundle)
+        .args(refs.iter().map(|(name, _)| name));
+
+    if !pushed_tag {
+        command.args(["--not", "--remotes"]);
+    }
+
+    let status = command
+        .current_dir(dir)
+        .stdout(process::Stdio::null())
+        .status()?;
+
+    if !status.success() {
+        return Err(Err::SubcommandFailed(status.code()));
+    }
+
+    Ok(())
+}
+
+/// Remove the parents of a removed repository that are now empty, stopping at
+/// the base directory
+pub fn remove_empty_parents(dir: &path::Path, base_dir: &path::Path) {
+    let mut parent = dir.parent();
+
+    while let Some(p) = parent {
+        if p == base_dir || !p.starts_with(base_dir) || fs::remove_dir(p).is_err() {
+            break;
+        }
+
+        parent = p.parent();
+    }
+}
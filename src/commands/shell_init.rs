@@ -0,0 +1,50 @@
+use crate::{
+    err::Result,
+    options::{InitShell, ShellInitOptions},
+};
+
+const POSIX_INIT: &str = r#"gp() {
+    case "$1" in
+        cd)
+            shift
+            local dir
+            dir="$(git-project where "$@")" && cd "$dir"
+            ;;
+        clone)
+            shift
+            local dir
+            git-project clone "$@" &&
+                dir="$(git-project clone --dry-run "$@")" &&
+                cd "$dir"
+            ;;
+        *)
+            git-project "$@"
+            ;;
+    esac
+}
+"#;
+
+const FISH_INIT: &str = r#"function gp
+    switch "$argv[1]"
+        case cd
+            set -l dir (git-project where $argv[2..-1]); and cd $dir
+        case clone
+            git-project clone $argv[2..-1]
+            and set -l dir (git-project clone --dry-run $argv[2..-1])
+            and cd $dir
+        case '*'
+            git-project $argv
+    end
+end
+"#;
+
+pub fn run(opts: &ShellInitOptions) -> Result<()> {
+    let script = match opts.shell {
+        InitShell::Bash | InitShell::Zsh => POSIX_INIT,
+        InitShell::Fish => FISH_INIT,
+    };
+
+    print!("{}", script);
+
+    Ok(())
+}
@@ -0,0 +1,248 @@
+use crate::{
+    err::{Err, Result},
+    explore, info,
+    options::{LogFormat, StandupOptions},
+    util::PathRelativizeExtension,
+};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate};
+use rayon::prelude::*;
+use serde_json::{json, Value};
+use std::path;
+
+/// Length of the abbreviated commit ids shown in text and markdown
+const SHORT_ID_LEN: usize = 7;
+
+struct Repository {
+    path: path::PathBuf,
+    commits: Vec<Commit>,
+}
+
+struct Commit {
+    id: git2::Oid,
+    time: DateTime<FixedOffset>,
+    author: String,
+    summary: String,
+}
+
+/// Whose commits to report
+pub enum Author {
+    /// Authors whose name or email contains this, as given with `--author`
+    Pattern(String),
+    /// Authors with exactly this email, the configured `user.email`
+    Email(String),
+}
+
+impl Author {
+    /// Whether a commit author is one of these authors, ignoring case
+    pub fn matches(&self, name: &str, email: &str) -> bool {
+        match self {
+            Author::Pattern(pattern) => {
+                let pattern = pattern.to_lowercase();
+
+                name.to_lowercase().contains(&pattern) || email.to_lowercase().contains(&pattern)
+            }
+            Author::Email(expected) => email.eq_ignore_ascii_case(expected),
+        }
+    }
+}
+
+/// Which commits to report
+struct Filter<'a> {
+    author: Option<&'a str>,
+    all_authors: bool,
+    since: NaiveDate,
+    until: NaiveDate,
+    branches: &'a [String],
+}
+
+pub fn run(opts: &StandupOptions) -> Result<()> {
+    let base_dir = &opts.base.base_dir;
+    let paths = explore::find_repositories(base_dir, &opts.list)?;
+
+    let filter = Filter {
+        author: opts.author.as_deref(),
+        all_authors: opts.all_authors,
+        since: opts.since,
+        until: opts.until.unwrap_or_else(|| Local::now().date_naive()),
+        branches: &opts.branches,
+    };
+
+    let mut repos: Vec<Repository> = paths
+        .par_iter()
+        .map(|dir| gather(dir, &filter))
+        .filter_map(|result| match result {
+            Ok(x) => Some(x),
+            Err(e) => {
+                eprintln!("Error received: {}", e);
+                None
+            }
+        })
+        .filter(|repo| !repo.commits.is_empty())
+        .collect();
+
+    repos.sort_by(|a, b| a.path.cmp(&b.path));
+
+    match opts.format {
+        LogFormat::Text => print_text(&repos, base_dir),
+        LogFormat::Markdown => print_markdown(&repos, base_dir),
+        LogFormat::Json => print_json(&repos, base_dir),
+    }
+
+    Ok(())
+}
+
+fn gather(dir: &path::Path, filter: &Filter) -> Result<Repository> {
+    let repo = git2::Repository::open(dir)?;
+
+    let author = match filter.author {
+        Some(author) => Some(Author::Pattern(author.to_owned())),
+        None if filter.all_authors => None,
+        None => Some(Author::Email(
+            repo.config()?
+                .get_string("user.email")
+                .map_err(|_| Err::InvalidConfig {
+                    key: "user.email".into(),
+                    message: format!(
+                        "not set for {}, use --author or --all-authors",
+                        dir.display()
+                    ),
+                })?,
+        )),
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(git2::Sort::TIME);
+
+    if filter.branches.is_empty() {
+        revwalk.push_glob("refs/heads")?;
+    } else {
+        for branch in filter.branches {
+            // Repositories without the branch have nothing to report for it
+            if let Ok(commit) = repo
+                .revparse_single(branch)
+                .and_then(|o| o.peel_to_commit())
+            {
+                revwalk.push(commit.id())?;
+            }
+        }
+    }
+
+    let mut commits = Vec::new();
+
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+
+        // Commits are sorted by commit time, and a commit is never authored
+        // after it was committed
+        match local_date(commit.time()) {
+            Some(date) if date < filter.since => break,
+            _ => {}
+        }
+
+        let signature = commit.author();
+        let name = signature.name().unwrap_or("");
+        let email = signature.email().unwrap_or("");
+
+        if let Some(author) = &author {
+            if !author.matches(name, email) {
+                continue;
+            }
+        }
+
+        let date = match local_date(signature.when()) {
+            Some(date) => date,
+            None => continue,
+        };
+
+        if date < filter.since || date > filter.until {
+            continue;
+        }
+
+        if let Some(time) = info::git_time(signature.when()) {
+            commits.push(Commit {
+                id: commit.id(),
+                time,
+                author: format!("{} <{}>", name, email),
+                summary: commit.summary().unwrap_or("").to_owned(),
+            });
+        }
+    }
+
+    Ok(Repository {
+        path: dir.to_path_buf(),
+        commits,
+    })
+}
+
+fn local_date(time: git2::Time) -> Option<NaiveDate> {
+    DateTime::from_timestamp(time.seconds(), 0).map(|t| t.with_timezone(&Local).date_naive())
+}
+
+fn short_id(id: git2::Oid) -> String {
+    id.to_string()[..SHORT_ID_LEN].to_owned()
+}
+
+fn print_text(repos: &[Repository], base_dir: &path::Path) {
+    let mut total = 0;
+
+    for repo in repos {
+        println!("{}", repo.path.normalize_relative_to(base_dir).display());
+
+        for commit in &repo.commits {
+            println!(
+                "  {} {} {}",
+                short_id(commit.id),
+                commit.time.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+                commit.summary
+            );
+        }
+
+        println!();
+        total += repo.commits.len();
+    }
+
+    println!("{} commits in {} repositories", total, repos.len());
+}
+
+fn print_markdown(repos: &[Repository], base_dir: &path::Path) {
+    for repo in repos {
+        println!(
+            "### {}",
+            repo.path.normalize_relative_to(base_dir).display()
+        );
+        println!();
+
+        for commit in &repo.commits {
+            println!("- {} (`{}`)", commit.summary, short_id(commit.id));
+        }
+
+        println!();
+    }
+}
+
+fn print_json(repos: &[Repository], base_dir: &path::Path) {
+    let repos: Vec<Value> = repos
+        .iter()
+        .map(|repo| {
+            let commits: Vec<Value> = repo
+                .commits
+                .iter()
+                .map(|commit| {
+                    json!({
+                        "id": commit.id.to_string(),
+                        "time": commit.time.to_rfc3339(),
+                        "author": commit.author,
+                        "summary": commit.summary,
+                    })
+                })
+                .collect();
+
+            json!({
+                "path": repo.path.normalize_relative_to(base_dir).display().to_string(),
+                "commits": commits,
+            })
+        })
+        .collect();
+
+    println!("{}", Value::Array(repos));
+}
@@ -0,0 +1,311 @@
+use crate::{
+    err::Result,
+    explore,
+    options::StatsOptions,
+    util::{self, PathRelativizeExtension},
+};
+use rayon::prelude::*;
+use std::{collections::BTreeMap, fs, path};
+
+/// Disk usage of one repository, in bytes
+#[derive(Default)]
+pub struct Usage {
+    /// Everything in the `.git` directory
+    pub git: u64,
+    /// Files in the working tree that are not ignored
+    pub worktree: u64,
+    /// Ignored files in the working tree, such as build output
+    pub ignored: u64,
+    pub packs: usize,
+    pub loose_objects: usize,
+}
+
+impl Usage {
+    pub fn total(&self) -> u64 {
+        self.git + self.worktree + self.ignored
+    }
+
+    fn add(&mut self, other: &Usage) {
+        self.git += other.git;
+        self.worktree += other.worktree;
+        self.ignored += other.ignored;
+        self.packs += other.packs;
+        self.loose_objects += other.loose_objects;
+    }
+}
+
+/// Totals for all repositories sharing a host, or a host and owner
+#[derive(Default)]
+struct Total {
+    usage: Usage,
+    repositories: usize,
+}
+
+pub fn run(opts: &StatsOptions) -> Result<()> {
+    let base_dir = &opts.base.base_dir;
+    let paths = explore::find_repositories(base_dir, &opts.list)?;
+
+    let mut repos: Vec<(path::PathBuf, Usage)> = paths
+        .par_iter()
+        .map(|dir| {
+            measure(dir, opts.list.deep_recurse)
+                .map(|usage| (dir.normalize_relative_to(base_dir), usage))
+        })
+        .filter_map(|result| match result {
+            Ok(x) => Some(x),
+            Err(e) => {
+                eprintln!("Error received: {}", e);
+                None
+            }
+        })
+        .collect();
+
+    repos.sort_by(|(a_path, a), (b_path, b)| b.total().cmp(&a.total()).then(a_path.cmp(b_path)));
+
+    let rows: Vec<[String; 7]> = repos
+        .iter()
+        .map(|(path, usage)| {
+            [
+                util::format_size(usage.total()),
+                util::format_size(usage.git),
+                util::format_size(usage.worktree),
+                util::format_size(usage.ignored),
+                usage.packs.to_string(),
+                usage.loose_objects.to_string(),
+                path.display().to_string(),
+            ]
+        })
+        .collect();
+
+    let headers = [
+        "TOTAL",
+        ".GIT",
+        "WORKTREE",
+        "IGNORED",
+        "PACKS",
+        "LOOSE",
+        "REPOSITORY",
+    ];
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    print_row(&headers, &widths);
+
+    for row in &rows {
+        print_row(row, &widths);
+    }
+
+    println!();
+    print_totals(&repos);
+
+    Ok(())
+}
+
+/// Print a row with the sizes and counts aligned to the right and the path
+/// last
+fn print_row<S: AsRef<str>>(cells: &[S], widths: &[usize]) {
+    let last = cells.len() - 1;
+    let mut line = String::new();
+
+    for (i, cell) in cells.iter().enumerate() {
+        if i == last {
+            line.push_str(cell.as_ref());
+        } else {
+            line.push_str(&format!("{:>width$}  ", cell.as_ref(), width = widths[i]));
+        }
+    }
+
+    println!("{}", line);
+}
+
+fn print_totals(repos: &[(path::PathBuf, Usage)]) {
+    let mut hosts: BTreeMap<String, (Total, BTreeMap<String, Total>)> = BTreeMap::new();
+    let mut all = Total::default();
+
+    for (path, usage) in repos {
+        let (host, owner) = host_and_owner(path);
+        let (host_total, owners) = hosts.entry(host).or_default();
+
+        for total in [&mut all, host_total, owners.entry(owner).or_default()] {
+            total.usage.add(usage);
+            total.repositories += 1;
+        }
+    }
+
+    println!("Totals");
+
+    for (host, (host_total, owners)) in &hosts {
+        print_total(host, host_total, 0);
+
+        // Repositories directly under a host have no owner to break it down by
+        for (owner, owner_total) in owners.iter().filter(|(owner, _)| *owner != host) {
+            print_total(owner, owner_total, 2);
+        }
+    }
+
+    print_total("all", &all, 0);
+}
+
+fn print_total(name: &str, total: &Total, indent: usize) {
+    println!(
+        "{:indent$}{}: {} ({} .git, {} ignored) in {} repositories",
+        "",
+        name,
+        util::format_size(total.usage.total()),
+        util::format_size(total.usage.git),
+        util::format_size(total.usage.ignored),
+        total.repositories,
+        indent = indent
+    );
+}
+
+/// The host and `host/owner` a repository path belongs to, such as
+/// `github.com` and `github.com/acme` for `github.com/acme/api`
+pub fn host_and_owner(relative_path: &path::Path) -> (String, String) {
+    let components: Vec<String> = relative_path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+
+    match components.as_slice() {
+        [host, owner, _, ..] => (host.clone(), format!("{}/{}", host, owner)),
+        [host, _] => (host.clone(), host.clone()),
+        _ => (".".into(), ".".into()),
+    }
+}
+
+/// Measure the disk usage of a repository. With `deep_recurse`, the
+/// repositories nested in it are measured on their own and left out.
+pub fn measure(dir: &path::Path, deep_recurse: bool) -> Result<Usage> {
+    let repo = git2::Repository::open(dir)?;
+    let mut usage = Usage {
+        git: dir_size(repo.path())?,
+        ..Usage::default()
+    };
+
+    let objects = repo.path().join("objects");
+
+    if let Ok(entries) = fs::read_dir(objects.join("pack")) {
+        usage.packs = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|e| e == "pack"))
+            .count();
+    }
+
+    if let Ok(entries) = fs::read_dir(&objects) {
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+
+            // Loose objects are kept in directories named after the first two
+            // hex digits of their id
+            if name.len() == 2 && name.chars().all(|c| c.is_ascii_hexdigit()) {
+                usage.loose_objects += fs::read_dir(entry.path())?.count();
+            }
+        }
+    }
+
+    if let Some(workdir) = repo.workdir() {
+        measure_worktree(&repo, workdir, "", deep_recurse, &mut usage)?;
+    }
+
+    Ok(usage)
+}
+
+/// Add up the files of a working tree directory. Ignored directories are
+/// counted as a whole. Nested repositories are added in, unless
+/// `--deep-recurse` measures them separately.
+fn measure_worktree(
+    repo: &git2::Repository,
+    workdir: &path::Path,
+    relative: &str,
+    deep_recurse: bool,
+    usage: &mut Usage,
+) -> Result<()> {
+    for entry in fs::read_dir(workdir.join(relative))? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let name = entry.file_name();
+
+        if name == ".git" {
+            continue;
+        }
+
+        let path = format!("{}{}", relative, name.to_string_lossy());
+
+        if file_type.is_dir() {
+            let dir = format!("{}/", path);
+
+            let nested_git = entry.path().join(".git");
+
+            if nested_git.is_dir() {
+                if !deep_recurse {
+                    usage.add(&measure(&entry.path(), false)?);
+                }
+            } else if nested_git.exists() {
+                // Submodules and linked worktrees keep their git directory
+                // elsewhere, such as in `.git/modules`, and are never
+                // measured on their own
+                let nested = git2::Repository::open(entry.path())?;
+                measure_worktree(&nested, &entry.path(), "", deep_recurse, usage)?;
+            } else if repo.is_path_ignored(&dir)? {
+                usage.ignored += ignored_dir_size(&entry.path(), deep_recurse)?;
+            } else {
+                measure_worktree(repo, workdir, &dir, deep_recurse, usage)?;
+            }
+        } else if file_type.is_file() {
+            let size = entry.metadata()?.len();
+
+            if repo.is_path_ignored(&path)? {
+                usage.ignored += size;
+            } else {
+                usage.worktree += size;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Total size in bytes of the files in an ignored directory. Like
+/// `measure_worktree`, this leaves out the repositories nested in it only
+/// when `--deep-recurse` measures them separately.
+fn ignored_dir_size(dir: &path::Path, deep_recurse: bool) -> Result<u64> {
+    let mut size = 0;
+
+    let entries = walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|entry| {
+            !(deep_recurse && entry.file_type().is_dir() && entry.path().join(".git").is_dir())
+        });
+
+    for entry in entries {
+        let entry = entry?;
+
+        if entry.file_type().is_file() {
+            size += entry.metadata()?.len();
+        }
+    }
+
+    Ok(size)
+}
+
+/// Total size in bytes of the files in a directory and its subdirectories
+fn dir_size(dir: &path::Path) -> Result<u64> {
+    let mut size = 0;
+
+    for entry in walkdir::WalkDir::new(dir) {
+        let entry = entry?;
+
+        if entry.file_type().is_file() {
+            size += entry.metadata()?.len();
+        }
+    }
+
+    Ok(size)
+}
@@ -0,0 +1,220 @@
+use crate::{
+    commands::{check, lookup, prune_branches},
+    err::{Err, Result},
+    explore,
+    options::SwitchOptions,
+    remotes::RemotePreference,
+    util::PathRelativizeExtension,
+};
+use rayon::prelude::*;
+use std::{fmt, path};
+
+/// What happened to one repository
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Switched,
+    AlreadyOn,
+    /// Created a local branch tracking this remote branch
+    Tracking(String),
+    /// Created a new branch at this starting point
+    Created(String),
+    Dirty,
+    Missing,
+    Exists,
+    NoDefaultBranch,
+    Failed(String),
+}
+
+impl Outcome {
+    fn succeeded(&self) -> bool {
+        match self {
+            Outcome::Switched | Outcome::AlreadyOn | Outcome::Tracking(_) | Outcome::Created(_) => {
+                true
+            }
+            Outcome::Dirty
+            | Outcome::Missing
+            | Outcome::Exists
+            | Outcome::NoDefaultBranch
+            | Outcome::Failed(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Switched => write!(f, "switched"),
+            Outcome::AlreadyOn => write!(f, "already on branch"),
+            Outcome::Tracking(upstream) => write!(f, "created tracking {}", upstream),
+            Outcome::Created(start) => write!(f, "created from {}", start),
+            Outcome::Dirty => write!(f, "refused, working directory is dirty"),
+            Outcome::Missing => write!(f, "no such branch"),
+            Outcome::Exists => write!(f, "branch already exists"),
+            Outcome::NoDefaultBranch => write!(f, "no remote default branch"),
+            Outcome::Failed(e) => write!(f, "failed: {}", e),
+        }
+    }
+}
+
+pub fn run(opts: &SwitchOptions) -> Result<()> {
+    let base_dir = &opts.base.base_dir;
+    let preference = RemotePreference::load(&opts.remote)?;
+
+    // --all or --group select repositories without naming them
+    let mut paths = if opts.repos.is_empty() {
+        explore::find_repositories(base_dir, &opts.list)?
+    } else {
+        opts.repos
+            .iter()
+            .map(|repo| lookup::resolve_repository(base_dir, repo, &opts.list))
+            .collect::<Result<_>>()?
+    };
+
+    paths.sort();
+    paths.dedup();
+
+    let outcomes: Vec<(path::PathBuf, Outcome)> = paths
+        .par_iter()
+        .map(|dir| {
+            let outcome =
+                switch(dir, opts, &preference).unwrap_or_else(|e| Outcome::Failed(e.to_string()));
+            (dir.normalize_relative_to(base_dir), outcome)
+        })
+        .collect();
+
+    let width = outcomes
+        .iter()
+        .map(|(path, _)| path.display().to_string().chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("REPOSITORY".len());
+
+    println!("{:width$}  RESULT", "REPOSITORY", width = width);
+
+    for (path, outcome) in &outcomes {
+        println!(
+            "{:width$}  {}",
+            path.display().to_string(),
+            outcome,
+            width = width
+        );
+    }
+
+    let failed = outcomes.iter().filter(|(_, o)| !o.succeeded()).count();
+
+    if failed > 0 {
+        return Err(Err::NotSwitched {
+            failed,
+            total: outcomes.len(),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn switch(
+    dir: &path::Path,
+    opts: &SwitchOptions,
+    preference: &RemotePreference,
+) -> Result<Outcome> {
+    let repo = git2::Repository::open(dir)?;
+    let branch_ref = format!("refs/heads/{}", opts.branch);
+    let exists = repo.find_reference(&branch_ref).is_ok();
+
+    let on_branch = repo
+        .head()
+        .ok()
+        .and_then(|head| head.name().map(|name| name == branch_ref))
+        .unwrap_or(false);
+
+    if on_branch && !opts.create {
+        return Ok(Outcome::AlreadyOn);
+    }
+
+    if opts.create && exists {
+        return Ok(Outcome::Exists);
+    }
+
+    if !check::is_clean(&repo)? {
+        return Ok(Outcome::Dirty);
+    }
+
+    let outcome = if exists {
+        Outcome::Switched
+    } else if opts.create {
+        let (start, commit) = if opts.from_default {
+            let remote = match preference.canonical_remote(&repo)? {
+                Some(remote) => remote,
+                None => return Ok(Outcome::NoDefaultBranch),
+            };
+
+            match prune_branches::default_branch(&repo, &remote.name) {
+                Some((name, oid)) => (name, repo.find_commit(oid)?),
+                None => return Ok(Outcome::NoDefaultBranch),
+            }
+        } else {
+            ("HEAD".to_owned(), repo.head()?.peel_to_commit()?)
+        };
+
+        repo.branch(&opts.branch, &commit, false)?;
+        Outcome::Created(start)
+    } else {
+        let upstream = match remote_branch(&repo, &opts.branch, preference)? {
+            Some(upstream) => upstream,
+            None => return Ok(Outcome::Missing),
+        };
+
+        let commit = repo
+            .find_reference(&format!("refs/remotes/{}", upstream))?
+            .peel_to_commit()?;
+
+        let mut branch = repo.branch(&opts.branch, &commit, false)?;
+        branch.set_upstream(Some(&upstream))?;
+        Outcome::Tracking(upstream)
+    };
+
+    if let Err(e) = check_out(&repo, &branch_ref) {
+        // A branch created for the switch is not left behind when it fails
+        if !exists {
+            repo.find_branch(&opts.branch, git2::BranchType::Local)?
+                .delete()?;
+        }
+
+        return Err(e);
+    }
+
+    Ok(outcome)
+}
+
+/// Check out a branch, refusing to overwrite local changes
+fn check_out(repo: &git2::Repository, branch_ref: &str) -> Result<()> {
+    let target = repo.revparse_single(branch_ref)?;
+    repo.checkout_tree(&target, Some(git2::build::CheckoutBuilder::new().safe()))?;
+    repo.set_head(branch_ref)?;
+
+    Ok(())
+}
+
+/// The remote-tracking branch to create a missing local branch from, such as
+/// `origin/feature`, preferring the canonical remote
+fn remote_branch(
+    repo: &git2::Repository,
+    branch: &str,
+    preference: &RemotePreference,
+) -> Result<Option<String>> {
+    let mut remotes: Vec<String> = Vec::new();
+
+    if let Some(remote) = preference.canonical_remote(repo)? {
+        remotes.push(remote.name);
+    }
+
+    remotes.extend(repo.remotes()?.iter().flatten().map(str::to_owned));
+
+    Ok(remotes
+        .into_iter()
+        .map(|remote| format!("{}/{}", remote, branch))
+        .find(|upstream| {
+            repo.find_reference(&format!("refs/remotes/{}", upstream))
+                .is_ok()
+        }))
+}
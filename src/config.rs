@@ -0,0 +1,134 @@
+use crate::err::Result;
+
+const SECTION: &str = "gitproject";
+
+/// Settings for `git-project`, read from the user's git config.
+///
+/// Settings live in the `gitproject` section. Any setting can be overridden
+/// for a single host by placing it in a subsection named after that host:
+///
+/// ```text
+/// [gitproject]
+///     remote = origin
+/// [gitproject "github.com"]
+///     remote = upstream
+///     remote = origin
+/// ```
+#[derive(Default)]
+pub struct Config {
+    entries: Vec<(String, String)>,
+}
+
+impl Config {
+    /// Load the global, XDG and system git config files
+    pub fn load() -> Result<Config> {
+        let config = git2::Config::open_default()?;
+
+        Config::from_git(&config)
+    }
+
+    pub fn from_git(config: &git2::Config) -> Result<Config> {
+        let mut entries = Vec::new();
+
+        for entry in &config.entries(Some("^gitproject\\."))? {
+            let entry = entry?;
+
+            if let (Some(name), Some(value)) = (entry.name(), entry.value()) {
+                entries.push((name.to_owned(), value.to_owned()));
+            }
+        }
+
+        Ok(Config { entries })
+    }
+
+    #[cfg(test)]
+    pub fn from_entries<I, K, V>(entries: I) -> Config
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        Config {
+            entries: entries
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        }
+    }
+
+    /// All values of `key` in the given subsection, in the order they were
+    /// configured
+    pub fn get_all(&self, subsection: Option<&str>, key: &str) -> Vec<&str> {
+        self.entries
+            .iter()
+            .filter(|(name, _)| name_matches(name, subsection, key))
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    /// All keys and values in a subsection, in the order they were configured
+    pub fn subsection_entries(&self, subsection: &str) -> Vec<(&str, &str)> {
+        self.entries
+            .iter()
+            .filter_map(|(name, value)| match split_name(name)? {
+                (Some(s), key) if s.eq_ignore_ascii_case(subsection) => Some((key, value.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// All values of `key` for `host`, falling back to the values in the
+    /// top-level `gitproject` section if the host has none of its own
+    pub fn host_values(&self, host: Option<&str>, key: &str) -> Vec<&str> {
+        if let Some(host) = host {
+            let values = self.get_all(Some(host), key);
+
+            if !values.is_empty() {
+                return values;
+            }
+        }
+
+        self.get_all(None, key)
+    }
+}
+
+/// git normalizes section and key names to lowercase, but keeps the case of
+/// subsections. Hosts are case insensitive, so subsections are compared
+/// without case as well.
+fn name_matches(name: &str, subsection: Option<&str>, key: &str) -> bool {
+    let (entry_subsection, entry_key) = match split_name(name) {
+        Some(split) => split,
+        None => return false,
+    };
+
+    if !entry_key.eq_ignore_ascii_case(key) {
+        return false;
+    }
+
+    match (entry_subsection, subsection) {
+        (None, None) => true,
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+        _ => false,
+    }
+}
+
+/// The subsection and key of a `gitproject` entry name
+fn split_name(name: &str) -> Option<(Option<&str>, &str)> {
+    let rest = strip_prefix_ignore_case(name, SECTION)?.strip_prefix('.')?;
+
+    match rest.rfind('.') {
+        Some(i) => Some((Some(&rest[..i]), &rest[i + 1..])),
+        None => Some((None, rest)),
+    }
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    if s.len() >= prefix.len()
+        && s.is_char_boundary(prefix.len())
+        && s[..prefix.len()].eq_ignore_ascii_case(prefix)
+    {
+        Some(&s[prefix.len()..])
+    } else {
+        None
+    }
+}
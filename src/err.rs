@@ -1,5 +1,6 @@
+use crate::options::Protocol;
 use auto_from::From;
-use std::{fmt, io};
+use std::{fmt, io, path};
 
 pub type Result<T> = std::result::Result<T, Err>;
 
@@ -12,6 +13,38 @@ pub enum Err {
     Git2(git2::Error),
     Walkdir(walkdir::Error),
     SubcommandFailed(Option<i32>),
+    NoMatchingRepository(String),
+    InvalidConfig {
+        key: String,
+        message: String,
+    },
+    ErrorWarnings(usize),
+    Json(serde_json::Error),
+    InvalidBaseline {
+        path: path::PathBuf,
+        message: String,
+    },
+    InvalidArgument {
+        name: &'static str,
+        message: String,
+    },
+    AmbiguousRepository {
+        query: String,
+        matches: Vec<path::PathBuf>,
+    },
+    UnpushedWork(path::PathBuf),
+    UnverifiedTags {
+        path: path::PathBuf,
+        remotes: Vec<String>,
+    },
+    NotSwitched {
+        failed: usize,
+        total: usize,
+    },
+    UnconvertibleUrl {
+        url: String,
+        protocol: Protocol,
+    },
 }
 
 impl fmt::Display for Err {
@@ -27,6 +60,37 @@ impl fmt::Display for Err {
                 format!("Subcommand failed with exit code {}", code)
             }
             Err::SubcommandFailed(None) => "Subcommand failed".into(),
+            Err::NoMatchingRepository(query) => format!("No repository matches {}", query),
+            Err::InvalidConfig { key, message } => format!("Invalid {}: {}", key, message),
+            Err::ErrorWarnings(count) => format!("{} warnings with error severity", count),
+            Err::Json(e) => format!("JSON Error: {}", e),
+            Err::InvalidBaseline { path, message } => {
+                format!("Invalid baseline {}: {}", path.display(), message)
+            }
+            Err::InvalidArgument { name, message } => format!("Invalid {}: {}", name, message),
+            Err::AmbiguousRepository { query, matches } => {
+                let matches: Vec<_> = matches.iter().map(|m| m.display().to_string()).collect();
+                format!(
+                    "{} matches several repositories: {}",
+                    query,
+                    matches.join(", ")
+                )
+            }
+            Err::UnpushedWork(path) => format!(
+                "{} has work that is not pushed, use --force to remove it anyway",
+                path.display()
+            ),
+            Err::UnverifiedTags { path, remotes } => format!(
+                "could not reach {} to check which tags of {} are pushed, use --force to remove it anyway",
+                remotes.join(", "),
+                path.display()
+            ),
+            Err::NotSwitched { failed, total } => {
+                format!("{} of {} repositories were not switched", failed, total)
+            }
+            Err::UnconvertibleUrl { url, protocol } => {
+                format!("{} cannot be converted to {}", url, protocol)
+            }
         };
 
         write!(f, "{}", s)
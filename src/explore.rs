@@ -1,6 +1,10 @@
-use crate::err::Result;
+use crate::{
+    err::Result,
+    groups::Groups,
+    options::BaseListOptions,
+    util::{PathFilter, PathRelativizeExtension},
+};
 use std::path;
-use walkdir;
 
 const MAX_DEPTH: usize = 100;
 
@@ -14,6 +18,30 @@ where
     Ok(paths)
 }
 
+/// The repositories under the base directory, limited to the groups
+/// selected with `--group`
+pub fn find_repositories(
+    base_path: &path::Path,
+    list: &BaseListOptions,
+) -> Result<Vec<path::PathBuf>> {
+    let paths = find_git_folders(base_path, list.deep_recurse)?;
+
+    if list.groups.is_empty() {
+        return Ok(paths);
+    }
+
+    let patterns = Groups::load()?.patterns(&list.groups)?;
+    let filter = PathFilter::new(&patterns, "group member")?;
+
+    Ok(paths
+        .into_iter()
+        .filter(|dir| {
+            let relative = dir.as_path().normalize_relative_to(base_path);
+            filter.matches(&relative.to_string_lossy().replace('\\', "/"))
+        })
+        .collect())
+}
+
 fn collect_git_folders<P, R>(
     base_path: P,
     child_path: R,
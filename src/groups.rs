@@ -0,0 +1,184 @@
+use crate::{
+    config::Config,
+    err::{Err, Result},
+};
+use std::{collections::BTreeMap, process};
+
+/// The subsection of the `gitproject` config section holding the groups
+const SUBSECTION: &str = "group";
+
+/// Named sets of repositories, such as the services making up one product.
+///
+/// Each group is a key in the `group` subsection, with one value per member.
+/// A member is a repository path relative to the base directory, a pattern
+/// with `*`, `?` or `**` wildcards, or the name of another group:
+///
+/// ```text
+/// [gitproject "group"]
+///     payments = github.com/acme/payments-*
+///     payments = github.com/acme/ledger
+///     checkout = payments
+///     checkout = github.com/acme/cart
+/// ```
+///
+/// git ignores the case of keys, so group names are compared without case.
+pub struct Groups {
+    members: BTreeMap<String, Vec<String>>,
+}
+
+impl Groups {
+    pub fn load() -> Result<Groups> {
+        Ok(Groups::new(&Config::load()?))
+    }
+
+    pub fn new(config: &Config) -> Groups {
+        let mut members: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        for (name, member) in config.subsection_entries(SUBSECTION) {
+            members
+                .entry(name.to_lowercase())
+                .or_default()
+                .push(member.to_owned());
+        }
+
+        Groups { members }
+    }
+
+    /// Every group and its members, ordered by name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.members
+            .iter()
+            .map(|(name, members)| (name.as_str(), members.as_slice()))
+    }
+
+    pub fn members(&self, name: &str) -> Option<&[String]> {
+        self.members.get(&name.to_lowercase()).map(Vec::as_slice)
+    }
+
+    /// Whether a member names another group rather than repositories
+    pub fn is_group(&self, member: &str) -> bool {
+        !member.contains('/') && self.members.contains_key(&member.to_lowercase())
+    }
+
+    /// The repository paths and patterns of groups, with the groups they
+    /// contain expanded
+    pub fn patterns<S: AsRef<str>>(&self, names: &[S]) -> Result<Vec<String>> {
+        let mut patterns = Vec::new();
+
+        for name in names {
+            self.expand(name.as_ref(), &mut Vec::new(), &mut patterns)?;
+        }
+
+        Ok(patterns)
+    }
+
+    fn expand(
+        &self,
+        name: &str,
+        stack: &mut Vec<String>,
+        patterns: &mut Vec<String>,
+    ) -> Result<()> {
+        let name = name.to_lowercase();
+
+        let members = self
+            .members
+            .get(&name)
+            .ok_or_else(|| Err::InvalidArgument {
+                name: "group",
+                message: format!("no group named {}", name),
+            })?;
+
+        if stack.contains(&name) {
+            return Err(Err::InvalidConfig {
+                key: config_key(&name),
+                message: format!("group {} contains itself", name),
+            });
+        }
+
+        stack.push(name);
+
+        for member in members {
+            if self.is_group(member) {
+                self.expand(member, stack, patterns)?;
+            } else if !patterns.contains(member) {
+                patterns.push(member.clone());
+            }
+        }
+
+        stack.pop();
+
+        Ok(())
+    }
+}
+
+/// Group names have to be valid git config keys: a letter followed by
+/// letters, digits and `-`
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Add members to a group in the global git config, creating it if needed
+pub fn add(name: &str, members: &[String]) -> Result<()> {
+    let key = config_key(name);
+
+    for member in members {
+        git_config(&["--add", &key, member])?;
+    }
+
+    Ok(())
+}
+
+/// Remove members from a group in the global git config, or the whole group
+/// if no members are given
+pub fn remove(name: &str, members: &[String]) -> Result<()> {
+    let key = config_key(name);
+
+    if members.is_empty() {
+        return git_config(&["--unset-all", &key]);
+    }
+
+    // `--fixed-value` would need git 2.30, so the member is matched with a
+    // regular expression instead
+    for member in members {
+        git_config(&["--unset-all", &key, &value_regex(member)])?;
+    }
+
+    Ok(())
+}
+
+/// A POSIX extended regular expression matching exactly `value`, as
+/// `git config` takes to select values
+pub fn value_regex(value: &str) -> String {
+    let mut regex = String::from("^");
+
+    for c in value.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            regex.push('\\');
+        }
+
+        regex.push(c);
+    }
+
+    regex.push('$');
+    regex
+}
+
+fn config_key(name: &str) -> String {
+    format!("gitproject.{}.{}", SUBSECTION, name)
+}
+
+fn git_config(args: &[&str]) -> Result<()> {
+    let status = process::Command::new("git")
+        .args(["config", "--global"])
+        .args(args)
+        .status()?;
+
+    if !status.success() {
+        return Err(Err::SubcommandFailed(status.code()));
+    }
+
+    Ok(())
+}
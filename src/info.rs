@@ -0,0 +1,145 @@
+use crate::{commands::check, err::Result, remotes::RemotePreference};
+use chrono::{DateTime, FixedOffset, Utc};
+use std::{fs, path, time};
+
+/// Details about the state of a repository, as shown by `list`
+pub struct RepoInfo {
+    pub path: path::PathBuf,
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: Option<usize>,
+    pub behind: Option<usize>,
+    /// Whether there are changes not checked in, only gathered by
+    /// `gather_dirty` since it is comparatively slow
+    pub dirty: Option<bool>,
+    pub remote_url: Option<String>,
+    pub last_commit: Option<DateTime<FixedOffset>>,
+    pub author: Option<String>,
+    pub last_modified: Option<DateTime<FixedOffset>>,
+    pub size: Option<u64>,
+}
+
+impl RepoInfo {
+    pub fn gather(path: &path::Path, preference: &RemotePreference) -> Result<RepoInfo> {
+        let repo = git2::Repository::open(path)?;
+
+        let mut info = RepoInfo {
+            path: path.to_path_buf(),
+            branch: None,
+            upstream: None,
+            ahead: None,
+            behind: None,
+            dirty: None,
+            remote_url: preference.canonical_remote(&repo)?.map(|r| r.url),
+            last_commit: None,
+            author: None,
+            last_modified: None,
+            size: None,
+        };
+
+        // An unborn branch has no HEAD yet, so there is nothing more to learn
+        let head = match repo.head() {
+            Ok(head) => head,
+            Err(_) => return Ok(info),
+        };
+
+        if let Ok(commit) = head.peel_to_commit() {
+            info.last_commit = git_time(commit.time());
+            info.author = commit.author().name().map(String::from);
+        }
+
+        if !head.is_branch() {
+            return Ok(info);
+        }
+
+        let branch = git2::Branch::wrap(head);
+        info.branch = branch.name()?.map(String::from);
+
+        if let Ok(upstream) = branch.upstream() {
+            info.upstream = upstream.name()?.map(String::from);
+
+            if let (Some(local), Some(remote)) = (branch.get().target(), upstream.get().target()) {
+                let (ahead, behind) = repo.graph_ahead_behind(local, remote)?;
+                info.ahead = Some(ahead);
+                info.behind = Some(behind);
+            }
+        }
+
+        Ok(info)
+    }
+
+    pub fn gather_dirty(&mut self) -> Result<()> {
+        let repo = git2::Repository::open(&self.path)?;
+        self.dirty = Some(!check::is_clean(&repo)?);
+
+        Ok(())
+    }
+
+    /// The most recent modification time of the index or any tracked file in
+    /// the working directory. Untracked files are skipped so that build output
+    /// does not count as activity.
+    pub fn gather_last_modified(&mut self) -> Result<()> {
+        let repo = git2::Repository::open(&self.path)?;
+        let mut latest = modified(&repo.path().join("index"));
+
+        if let Some(workdir) = repo.workdir() {
+            for entry in repo.index()?.iter() {
+                let file = workdir.join(String::from_utf8_lossy(&entry.path).as_ref());
+                latest = latest.max(modified(&file));
+            }
+        }
+
+        self.last_modified = latest.map(|t| DateTime::<Utc>::from(t).into());
+
+        Ok(())
+    }
+
+    /// Total size in bytes of every file in the repository directory
+    pub fn gather_size(&mut self) -> Result<()> {
+        let mut size = 0;
+
+        for entry in walkdir::WalkDir::new(&self.path) {
+            let entry = entry?;
+
+            if entry.file_type().is_file() {
+                size += entry.metadata()?.len();
+            }
+        }
+
+        self.size = Some(size);
+
+        Ok(())
+    }
+
+    /// The later of the last commit and the last modification
+    pub fn last_activity(&self) -> Option<DateTime<FixedOffset>> {
+        self.last_commit.max(self.last_modified)
+    }
+}
+
+/// A cheap measure of when a repository was last used: the later of the last
+/// commit on HEAD and the last change to the index
+pub fn recent_activity(path: &path::Path) -> Option<DateTime<FixedOffset>> {
+    let repo = git2::Repository::open(path).ok()?;
+
+    let commit_time = repo
+        .head()
+        .and_then(|head| head.peel_to_commit())
+        .ok()
+        .and_then(|commit| git_time(commit.time()));
+
+    let index_time = modified(&repo.path().join("index")).map(|t| DateTime::<Utc>::from(t).into());
+
+    commit_time.max(index_time)
+}
+
+fn modified(path: &path::Path) -> Option<time::SystemTime> {
+    fs::symlink_metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Convert a git timestamp into a date in the committer's timezone
+pub fn git_time(time: git2::Time) -> Option<DateTime<FixedOffset>> {
+    let offset = FixedOffset::east_opt(time.offset_minutes() * 60)?;
+
+    DateTime::from_timestamp(time.seconds(), 0).map(|t| t.with_timezone(&offset))
+}
@@ -1,10 +1,20 @@
+mod baseline;
 mod commands;
+mod config;
 pub mod err;
 mod explore;
+mod groups;
+mod info;
 pub mod options;
+mod policy;
+mod remotes;
+mod tree;
 mod util;
 
-use crate::commands::{check, clone, list, organize, gen_completions};
+use crate::commands::{
+    check, clone, complete, duplicates, gen_completions, gen_docs, grep, group, list, lookup, mv,
+    organize, prune_branches, remote_rewrite, rm, shell_init, standup, stats, switch,
+};
 
 #[cfg(test)]
 mod test;
@@ -14,10 +24,24 @@ use crate::options::Options;
 
 pub fn run(opts: &Options) -> Result<()> {
     match &opts.command {
-        options::Command::Clone(clone_opts) => clone::run(&clone_opts),
-        options::Command::List(list_opts) => list::run(&list_opts),
-        options::Command::Check(list_opts) => check::run(&list_opts),
-        options::Command::Organize(organize_opts) => organize::run(&organize_opts),
-        options::Command::GenCompletions(completion_opts) => gen_completions::run(&completion_opts),
+        options::Command::Clone(clone_opts) => clone::run(clone_opts),
+        options::Command::List(list_opts) => list::run(list_opts),
+        options::Command::Check(list_opts) => check::run(list_opts),
+        options::Command::Organize(organize_opts) => organize::run(organize_opts),
+        options::Command::GenCompletions(completion_opts) => gen_completions::run(completion_opts),
+        options::Command::Duplicates(duplicates_opts) => duplicates::run(duplicates_opts),
+        options::Command::PruneBranches(prune_opts) => prune_branches::run(prune_opts),
+        options::Command::Mv(mv_opts) => mv::run(mv_opts),
+        options::Command::RemoteRewrite(rewrite_opts) => remote_rewrite::run(rewrite_opts),
+        options::Command::Grep(grep_opts) => grep::run(grep_opts),
+        options::Command::Rm(rm_opts) => rm::run(rm_opts),
+        options::Command::Switch(switch_opts) => switch::run(switch_opts),
+        options::Command::Standup(standup_opts) => standup::run(standup_opts),
+        options::Command::Stats(stats_opts) => stats::run(stats_opts),
+        options::Command::Where(where_opts) => lookup::run(where_opts),
+        options::Command::Group(group_command) => group::run(group_command),
+        options::Command::ShellInit(init_opts) => shell_init::run(init_opts),
+        options::Command::GenDocs(docs_opts) => gen_docs::run(docs_opts),
+        options::Command::Complete(complete_opts) => complete::run(complete_opts),
     }
 }
@@ -1,4 +1,4 @@
-use std::{ffi, path};
+use std::{ffi, fmt, path, str};
 use structopt::{clap, StructOpt};
 
 /// A manager for all of your git projects
@@ -15,6 +15,7 @@ pub struct BaseOptions {
         short = "-d",
         long = "--base-dir",
         env = "GIT_PROJECT_BASE_DIR",
+        raw(hide_env_values = "true"),
         parse(try_from_os_str = "parse_canonical_path")
     )]
     pub base_dir: path::PathBuf,
@@ -41,6 +42,62 @@ pub enum Command {
     /// Generate CLI completions for your shell of choice
     #[structopt(name = "gen-completions")]
     GenCompletions(CompletionOptions),
+
+    /// List repositories cloned more than once, grouped by remote URL
+    #[structopt(name = "duplicates")]
+    Duplicates(DuplicatesOptions),
+
+    /// Delete local branches merged into the remote default branch or whose upstream is gone
+    #[structopt(name = "prune-branches")]
+    PruneBranches(PruneBranchesOptions),
+
+    /// Move a repository to the location for a new clone URL, or to a new path
+    #[structopt(name = "mv")]
+    Mv(MvOptions),
+
+    /// Rewrite matching remote URLs in every repository, such as after a host moved domains
+    #[structopt(name = "remote-rewrite")]
+    RemoteRewrite(RemoteRewriteOptions),
+
+    /// Search the tracked files of every repository for a regular expression
+    #[structopt(name = "grep")]
+    Grep(GrepOptions),
+
+    /// Delete a repository, refusing if it has work that is not pushed
+    #[structopt(name = "rm")]
+    Rm(RmOptions),
+
+    /// Switch to or create the same branch in several repositories
+    #[structopt(name = "switch")]
+    Switch(SwitchOptions),
+
+    /// Show your commits across all repositories, such as since yesterday
+    #[structopt(name = "standup", raw(alias = r#""log""#))]
+    Standup(StandupOptions),
+
+    /// Show the disk usage of every repository, largest first, with totals per host and owner
+    #[structopt(name = "stats", raw(alias = r#""du""#))]
+    Stats(StatsOptions),
+
+    /// Print the path of the repository best matching a URL, owner/repo or part of a name
+    #[structopt(name = "where")]
+    Where(WhereOptions),
+
+    /// Manage named groups of repositories, which --group selects
+    #[structopt(name = "group")]
+    Group(GroupCommand),
+
+    /// Print shell functions that add `gp cd QUERY` and change directory after `gp clone`
+    #[structopt(name = "shell-init")]
+    ShellInit(ShellInitOptions),
+
+    /// Generate man pages and a markdown reference for every subcommand
+    #[structopt(name = "gen-docs")]
+    GenDocs(DocsOptions),
+
+    /// Print completion candidates, used by the scripts from gen-completions
+    #[structopt(name = "complete", raw(setting = "clap::AppSettings::Hidden"))]
+    Complete(CompleteOptions),
 }
 
 #[derive(StructOpt)]
@@ -52,6 +109,9 @@ pub struct CloneOptions {
     #[structopt(short = "-n", long = "--dry-run")]
     pub only_print_location: bool,
 
+    #[structopt(flatten)]
+    pub remote: RemoteOptions,
+
     /// The URL of the project to be cloned. Can be URL or ssh path
     #[structopt(name = "URL")]
     pub clone_url: String,
@@ -64,13 +124,453 @@ pub struct ListOptions {
 
     #[structopt(flatten)]
     pub list: BaseListOptions,
+
+    #[structopt(flatten)]
+    pub remote: RemoteOptions,
+
+    /// Show all details about each repository
+    #[structopt(short = "-l", long = "--long")]
+    pub long: bool,
+
+    /// Comma separated details to show about each repository
+    #[structopt(
+        long = "--columns",
+        name = "COLUMNS",
+        raw(use_delimiter = "true", possible_values = "&Column::variants()")
+    )]
+    pub columns: Vec<Column>,
+
+    /// Output format
+    #[structopt(
+        long = "--format",
+        name = "FORMAT",
+        default_value = "text",
+        raw(possible_values = "&Format::variants()")
+    )]
+    pub format: Format,
+
+    /// End each repository with a NUL character instead of a newline
+    #[structopt(short = "-0", long = "--null")]
+    pub null: bool,
+
+    /// Order to list repositories in. Dates and sizes are listed largest first
+    #[structopt(
+        long = "--sort",
+        name = "SORT",
+        raw(possible_values = "&SortKey::variants()")
+    )]
+    pub sort: Option<SortKey>,
+
+    /// Only list repositories with a commit or modification within this duration, such as 2w
+    #[structopt(
+        long = "--since",
+        name = "SINCE",
+        parse(try_from_str = "crate::util::parse_duration")
+    )]
+    pub since: Option<chrono::Duration>,
+
+    /// Only list repositories without a commit or modification within this duration, such as 6mo
+    #[structopt(
+        long = "--stale",
+        name = "STALE",
+        parse(try_from_str = "crate::util::parse_duration")
+    )]
+    pub stale: Option<chrono::Duration>,
+
+    /// Show repositories as a tree grouped by host and owner
+    #[structopt(
+        long = "--tree",
+        raw(conflicts_with_all = r#"&["long", "COLUMNS", "FORMAT", "null"]"#)
+    )]
+    pub tree: bool,
+
+    /// Mark each repository in the tree with * if dirty, and with ↑ and ↓ if ahead of or behind
+    /// its upstream
+    #[structopt(long = "--status", raw(requires = r#""tree""#))]
+    pub status: bool,
 }
 
-#[derive(StructOpt)]
+/// A detail shown about each repository by `list`
+#[derive(Clone, Copy, PartialEq)]
+pub enum Column {
+    Branch,
+    Upstream,
+    Ahead,
+    Behind,
+    Dirty,
+    Remote,
+    Date,
+    Author,
+    Modified,
+    Size,
+}
+
+impl Column {
+    pub fn variants() -> [&'static str; 10] {
+        [
+            "branch", "upstream", "ahead", "behind", "dirty", "remote", "date", "author",
+            "modified", "size",
+        ]
+    }
+
+    /// The columns shown by `--long`
+    pub fn long() -> Vec<Column> {
+        vec![
+            Column::Branch,
+            Column::Upstream,
+            Column::Ahead,
+            Column::Behind,
+            Column::Dirty,
+            Column::Remote,
+            Column::Date,
+            Column::Author,
+        ]
+    }
+}
+
+impl str::FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Column, String> {
+        match s {
+            "branch" => Ok(Column::Branch),
+            "upstream" => Ok(Column::Upstream),
+            "ahead" => Ok(Column::Ahead),
+            "behind" => Ok(Column::Behind),
+            "dirty" => Ok(Column::Dirty),
+            "remote" => Ok(Column::Remote),
+            "date" => Ok(Column::Date),
+            "author" => Ok(Column::Author),
+            "modified" => Ok(Column::Modified),
+            "size" => Ok(Column::Size),
+            _ => Err(format!("Unknown column {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Column::Branch => "branch",
+            Column::Upstream => "upstream",
+            Column::Ahead => "ahead",
+            Column::Behind => "behind",
+            Column::Dirty => "dirty",
+            Column::Remote => "remote",
+            Column::Date => "date",
+            Column::Author => "author",
+            Column::Modified => "modified",
+            Column::Size => "size",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SortKey {
+    Path,
+    LastCommit,
+    LastModified,
+    Size,
+    Host,
+}
+
+impl SortKey {
+    pub fn variants() -> [&'static str; 5] {
+        ["path", "last-commit", "last-modified", "size", "host"]
+    }
+}
+
+impl str::FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<SortKey, String> {
+        match s {
+            "path" => Ok(SortKey::Path),
+            "last-commit" => Ok(SortKey::LastCommit),
+            "last-modified" => Ok(SortKey::LastModified),
+            "size" => Ok(SortKey::Size),
+            "host" => Ok(SortKey::Host),
+            _ => Err(format!("Unknown sort order {}", s)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+    Tsv,
+}
+
+impl Format {
+    pub fn variants() -> [&'static str; 4] {
+        ["text", "json", "csv", "tsv"]
+    }
+}
+
+impl str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "tsv" => Ok(Format::Tsv),
+            _ => Err(format!("Unknown format {}", s)),
+        }
+    }
+}
+
+/// Output format of `standup`
+#[derive(Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    Text,
+    Markdown,
+    Json,
+}
+
+impl LogFormat {
+    pub fn variants() -> [&'static str; 3] {
+        ["text", "markdown", "json"]
+    }
+}
+
+impl str::FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<LogFormat, String> {
+        match s {
+            "text" => Ok(LogFormat::Text),
+            "markdown" => Ok(LogFormat::Markdown),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("Unknown format {}", s)),
+        }
+    }
+}
+
+/// A kind of warning reported by `check`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum WarningKind {
+    Dirty,
+    NoRemotes,
+    Ahead,
+    NotOnRemote,
+    NoUpstream,
+    UpstreamGone,
+    UnpushedTag,
+    Stash,
+    InProgress,
+    Detached,
+    Path,
+    Duplicate,
+    Protocol,
+    Credentials,
+    Identity,
+}
+
+impl WarningKind {
+    pub fn variants() -> [&'static str; 15] {
+        [
+            "dirty",
+            "no-remotes",
+            "ahead",
+            "not-on-remote",
+            "no-upstream",
+            "upstream-gone",
+            "unpushed-tag",
+            "stash",
+            "in-progress",
+            "detached",
+            "path",
+            "duplicate",
+            "protocol",
+            "credentials",
+            "identity",
+        ]
+    }
+
+    /// The severity used unless the kind is configured otherwise
+    pub fn default_severity(self) -> Severity {
+        match self {
+            WarningKind::NoUpstream | WarningKind::Path => Severity::Info,
+            WarningKind::Credentials => Severity::Error,
+            _ => Severity::Warn,
+        }
+    }
+
+    /// Whether the kind is reported without being turned on with `--warn`
+    pub fn enabled_by_default(self) -> bool {
+        // Finding duplicates opens every repository before any is checked
+        self != WarningKind::Duplicate
+    }
+}
+
+impl str::FromStr for WarningKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<WarningKind, String> {
+        match s {
+            "dirty" => Ok(WarningKind::Dirty),
+            "no-remotes" => Ok(WarningKind::NoRemotes),
+            "ahead" => Ok(WarningKind::Ahead),
+            "not-on-remote" => Ok(WarningKind::NotOnRemote),
+            "no-upstream" => Ok(WarningKind::NoUpstream),
+            "upstream-gone" => Ok(WarningKind::UpstreamGone),
+            "unpushed-tag" => Ok(WarningKind::UnpushedTag),
+            "stash" => Ok(WarningKind::Stash),
+            "in-progress" => Ok(WarningKind::InProgress),
+            "detached" => Ok(WarningKind::Detached),
+            "path" => Ok(WarningKind::Path),
+            "duplicate" => Ok(WarningKind::Duplicate),
+            "protocol" => Ok(WarningKind::Protocol),
+            "credentials" => Ok(WarningKind::Credentials),
+            "identity" => Ok(WarningKind::Identity),
+            _ => Err(format!("Unknown warning kind {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            WarningKind::Dirty => "dirty",
+            WarningKind::NoRemotes => "no-remotes",
+            WarningKind::Ahead => "ahead",
+            WarningKind::NotOnRemote => "not-on-remote",
+            WarningKind::NoUpstream => "no-upstream",
+            WarningKind::UpstreamGone => "upstream-gone",
+            WarningKind::UnpushedTag => "unpushed-tag",
+            WarningKind::Stash => "stash",
+            WarningKind::InProgress => "in-progress",
+            WarningKind::Detached => "detached",
+            WarningKind::Path => "path",
+            WarningKind::Duplicate => "duplicate",
+            WarningKind::Protocol => "protocol",
+            WarningKind::Credentials => "credentials",
+            WarningKind::Identity => "identity",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Severity {
+    Info,
+    Warn,
+    Error,
+}
+
+impl Severity {
+    pub fn variants() -> [&'static str; 3] {
+        ["info", "warn", "error"]
+    }
+}
+
+impl str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Severity, String> {
+        match s {
+            "info" => Ok(Severity::Info),
+            "warn" => Ok(Severity::Warn),
+            "error" => Ok(Severity::Error),
+            _ => Err(format!("Unknown severity {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Severity::Info => "info",
+            Severity::Warn => "warn",
+            Severity::Error => "error",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+/// A warning kind to enable, written as `KIND` or `KIND=SEVERITY`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WarningSetting {
+    pub kind: WarningKind,
+    pub severity: Option<Severity>,
+}
+
+impl str::FromStr for WarningSetting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<WarningSetting, String> {
+        let mut parts = s.splitn(2, '=');
+        let kind = parts.next().unwrap().trim().parse()?;
+        let severity = match parts.next() {
+            Some(severity) => Some(severity.trim().parse()?),
+            None => None,
+        };
+
+        Ok(WarningSetting { kind, severity })
+    }
+}
+
+/// The form of a clone URL
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Protocol {
+    Ssh,
+    Https,
+}
+
+impl Protocol {
+    pub fn variants() -> [&'static str; 2] {
+        ["ssh", "https"]
+    }
+}
+
+impl str::FromStr for Protocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Protocol, String> {
+        match s {
+            "ssh" => Ok(Protocol::Ssh),
+            "https" => Ok(Protocol::Https),
+            _ => Err(format!("Unknown protocol {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Protocol::Ssh => "ssh",
+            Protocol::Https => "https",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(StructOpt, Clone)]
 pub struct BaseListOptions {
     /// Do not stop recursing when a .git folder is found
     #[structopt(short = "-r", long = "--deep-recurse")]
     pub deep_recurse: bool,
+
+    /// Only include repositories in this group. May be given multiple times
+    #[structopt(long = "--group", name = "GROUP", raw(number_of_values = "1"))]
+    pub groups: Vec<String>,
+}
+
+#[derive(StructOpt)]
+pub struct RemoteOptions {
+    /// Name of the remote that decides where a repository belongs. May be given multiple times,
+    /// most preferred first
+    #[structopt(long = "--remote", name = "REMOTE", raw(number_of_values = "1"))]
+    pub remote: Vec<String>,
 }
 
 #[derive(StructOpt)]
@@ -81,15 +581,91 @@ pub struct CheckOptions {
     #[structopt(flatten)]
     pub base: BaseOptions,
 
+    #[structopt(flatten)]
+    pub remote: RemoteOptions,
+
     /// Print a summary of the repositories
     #[structopt(short = "-s", long = "--summarize")]
     pub summarize: bool,
+
+    /// Compare each local branch without an upstream with the branch of the same name on every remote
+    #[structopt(long = "--match-names")]
+    pub match_names: bool,
+
+    /// Ask every remote for its tags with `git ls-remote` to find tags that were not pushed,
+    /// instead of looking for tags that no remote-tracking branch contains
+    #[structopt(long = "--fetch")]
+    pub fetch: bool,
+
+    /// Report a kind of warning, optionally with a severity of info, warn or error, such as
+    /// `dirty=error`. May be comma separated or given multiple times
+    #[structopt(
+        long = "--warn",
+        name = "KIND[=SEVERITY]",
+        raw(use_delimiter = "true", number_of_values = "1")
+    )]
+    pub warn: Vec<WarningSetting>,
+
+    /// Do not report a kind of warning. May be comma separated or given multiple times
+    #[structopt(
+        long = "--allow",
+        name = "KIND",
+        raw(
+            use_delimiter = "true",
+            number_of_values = "1",
+            possible_values = "&WarningKind::variants()"
+        )
+    )]
+    pub allow: Vec<WarningKind>,
+
+    /// Baseline file of accepted warnings [default: BASE_DIR/.git-project-baseline.json]
+    #[structopt(long = "--baseline", name = "FILE", parse(from_os_str))]
+    pub baseline: Option<path::PathBuf>,
+
+    /// Record the current warnings in the baseline, so that later checks only report new or
+    /// changed warnings
+    #[structopt(long = "--update-baseline")]
+    pub update_baseline: bool,
+
+    /// Also report warnings that are in the baseline or snoozed
+    #[structopt(
+        long = "--no-baseline",
+        raw(conflicts_with_all = r#"&["update_baseline", "snooze"]"#)
+    )]
+    pub no_baseline: bool,
+
+    /// Stop reporting a kind of warning for a repository, given by its path relative to the base
+    /// directory, until the date given with --until
+    #[structopt(
+        long = "--snooze",
+        raw(
+            value_names = r#"&["REPO", "KIND"]"#,
+            number_of_values = "2",
+            requires = r#""UNTIL""#,
+            conflicts_with = r#""update_baseline""#
+        )
+    )]
+    pub snooze: Vec<String>,
+
+    /// Last day of a snooze, such as 2026-12-01 or 2w
+    #[structopt(
+        long = "--until",
+        name = "UNTIL",
+        parse(try_from_str = "crate::util::parse_date"),
+        raw(requires = r#""snooze""#)
+    )]
+    pub until: Option<chrono::NaiveDate>,
+
+    /// Write the user.name, user.email, user.signingkey and commit.gpgsign expected for each
+    /// repository's host into its local config
+    #[structopt(long = "--fix")]
+    pub fix: bool,
 }
 
 #[derive(StructOpt)]
 pub struct OrganizeOptions {
     /// Directory to organize
-    #[structopt(name = "DIR", parse(from_os_str))]
+    #[structopt(name = "DIR", parse(try_from_os_str = "parse_canonical_path"))]
     pub dir: path::PathBuf,
 
     /// Directory to place organized repositories in
@@ -99,6 +675,13 @@ pub struct OrganizeOptions {
     /// Print out the folders that will be moved without actually moving anything
     #[structopt(short = "-n", long = "--dry-run")]
     pub dry_run: bool,
+
+    /// Review each move before anything is moved
+    #[structopt(short = "-i", long = "--interactive")]
+    pub interactive: bool,
+
+    #[structopt(flatten)]
+    pub remote: RemoteOptions,
 }
 
 #[derive(StructOpt)]
@@ -114,6 +697,429 @@ pub struct CompletionOptions {
     pub shell: clap::Shell,
 }
 
+#[derive(StructOpt)]
+pub struct DuplicatesOptions {
+    #[structopt(flatten)]
+    pub base: BaseOptions,
+
+    #[structopt(flatten)]
+    pub list: BaseListOptions,
+
+    #[structopt(flatten)]
+    pub remote: RemoteOptions,
+}
+
+#[derive(StructOpt)]
+pub struct PruneBranchesOptions {
+    #[structopt(flatten)]
+    pub base: BaseOptions,
+
+    #[structopt(flatten)]
+    pub list: BaseListOptions,
+
+    #[structopt(flatten)]
+    pub remote: RemoteOptions,
+
+    /// Delete the branches instead of only listing them
+    #[structopt(long = "--apply")]
+    pub apply: bool,
+}
+
+#[derive(StructOpt)]
+pub struct MvOptions {
+    #[structopt(flatten)]
+    pub base: BaseOptions,
+
+    #[structopt(flatten)]
+    pub list: BaseListOptions,
+
+    #[structopt(flatten)]
+    pub remote: RemoteOptions,
+
+    /// Print what would change without changing anything
+    #[structopt(short = "-n", long = "--dry-run")]
+    pub dry_run: bool,
+
+    /// Do not set the URL of the preferred remote when moving to the location of a new URL
+    #[structopt(long = "--keep-url")]
+    pub keep_url: bool,
+
+    /// A clone URL, a path relative to the base directory, or an owner/repo suffix that only one
+    /// repository has
+    #[structopt(name = "REPO")]
+    pub repo: String,
+
+    /// The repository's new clone URL, or its new path relative to the base directory
+    #[structopt(name = "DESTINATION")]
+    pub destination: String,
+}
+
+#[derive(StructOpt)]
+pub struct RemoteRewriteOptions {
+    #[structopt(flatten)]
+    pub base: BaseOptions,
+
+    #[structopt(flatten)]
+    pub list: BaseListOptions,
+
+    #[structopt(flatten)]
+    pub remote: RemoteOptions,
+
+    /// Print what would change without changing anything
+    #[structopt(short = "-n", long = "--dry-run")]
+    pub dry_run: bool,
+
+    /// Start of the URLs to rewrite, or a regular expression with --regex
+    #[structopt(
+        long = "--from",
+        name = "PATTERN",
+        raw(requires = r#""REPLACEMENT""#, required_unless = r#""PROTOCOL""#)
+    )]
+    pub from: Option<String>,
+
+    /// Replacement for the part of the URL matched by --from. With --regex, may refer to capture
+    /// groups such as $1
+    #[structopt(long = "--to", name = "REPLACEMENT", raw(requires = r#""PATTERN""#))]
+    pub to: Option<String>,
+
+    /// Treat --from as a regular expression
+    #[structopt(long = "--regex", raw(requires = r#""PATTERN""#))]
+    pub regex: bool,
+
+    /// Convert the matching URLs to ssh or https form
+    #[structopt(
+        long = "--protocol",
+        name = "PROTOCOL",
+        raw(possible_values = "&Protocol::variants()")
+    )]
+    pub protocol: Option<Protocol>,
+
+    /// Move repositories to the path derived from their new URL
+    #[structopt(long = "--relocate")]
+    pub relocate: bool,
+}
+
+#[derive(StructOpt)]
+pub struct RmOptions {
+    #[structopt(flatten)]
+    pub base: BaseOptions,
+
+    #[structopt(flatten)]
+    pub list: BaseListOptions,
+
+    /// Delete the repository even if it has changes, stashes, commits or branches that are not
+    /// pushed
+    #[structopt(short = "-f", long = "--force")]
+    pub force: bool,
+
+    /// Save all unpushed branches, tags and the stash to this git bundle before deleting
+    #[structopt(long = "--bundle", name = "FILE", parse(from_os_str))]
+    pub bundle: Option<path::PathBuf>,
+
+    /// A clone URL, a path relative to the base directory, or an owner/repo suffix that only one
+    /// repository has
+    #[structopt(name = "REPO")]
+    pub repo: String,
+}
+
+#[derive(StructOpt)]
+pub struct GrepOptions {
+    #[structopt(flatten)]
+    pub base: BaseOptions,
+
+    #[structopt(flatten)]
+    pub list: BaseListOptions,
+
+    /// Ignore case when matching
+    #[structopt(short = "-i", long = "--ignore-case")]
+    pub ignore_case: bool,
+
+    /// Match the pattern as a plain string instead of a regular expression
+    #[structopt(short = "-F", long = "--fixed-strings")]
+    pub fixed_strings: bool,
+
+    /// Only print the names of files that match
+    #[structopt(short = "-l", long = "--files-with-matches")]
+    pub files_with_matches: bool,
+
+    /// Search the files at this revision, such as a branch or tag, instead of the working tree
+    #[structopt(long = "--rev", name = "REV")]
+    pub rev: Option<String>,
+
+    /// The regular expression to search for
+    #[structopt(name = "PATTERN")]
+    pub pattern: String,
+
+    /// Only search these files or directories. `*`, `?` and `**` are wildcards
+    #[structopt(name = "PATH")]
+    pub paths: Vec<String>,
+}
+
+#[derive(StructOpt)]
+pub struct StandupOptions {
+    #[structopt(flatten)]
+    pub base: BaseOptions,
+
+    #[structopt(flatten)]
+    pub list: BaseListOptions,
+
+    /// Only show commits whose author name or email contains this, instead of the user.email
+    /// configured for each repository
+    #[structopt(long = "--author", name = "AUTHOR")]
+    pub author: Option<String>,
+
+    /// Show commits by every author
+    #[structopt(long = "--all-authors", raw(conflicts_with = r#""AUTHOR""#))]
+    pub all_authors: bool,
+
+    /// First day to show commits from, as YYYY-MM-DD, today, yesterday or a duration ago like 1w
+    #[structopt(
+        long = "--since",
+        name = "SINCE",
+        default_value = "yesterday",
+        parse(try_from_str = "crate::util::parse_past_date")
+    )]
+    pub since: chrono::NaiveDate,
+
+    /// Last day to show commits from, in the same form as --since. Defaults to today
+    #[structopt(
+        long = "--until",
+        name = "UNTIL",
+        parse(try_from_str = "crate::util::parse_past_date")
+    )]
+    pub until: Option<chrono::NaiveDate>,
+
+    /// Only show commits on this branch instead of on any local branch. May be given multiple
+    /// times
+    #[structopt(long = "--branch", name = "BRANCH", raw(number_of_values = "1"))]
+    pub branches: Vec<String>,
+
+    /// Output format
+    #[structopt(
+        long = "--format",
+        name = "FORMAT",
+        default_value = "text",
+        raw(possible_values = "&LogFormat::variants()")
+    )]
+    pub format: LogFormat,
+}
+
+#[derive(StructOpt)]
+pub struct SwitchOptions {
+    #[structopt(flatten)]
+    pub base: BaseOptions,
+
+    #[structopt(flatten)]
+    pub list: BaseListOptions,
+
+    #[structopt(flatten)]
+    pub remote: RemoteOptions,
+
+    /// Create the branch instead of switching to an existing one
+    #[structopt(short = "-c", long = "--create")]
+    pub create: bool,
+
+    /// Create the branch from the default branch of the preferred remote instead of the
+    /// current commit
+    #[structopt(long = "--from-default", raw(requires = r#""create""#))]
+    pub from_default: bool,
+
+    /// Switch every repository under the base directory
+    #[structopt(long = "--all", raw(conflicts_with = r#""REPO""#))]
+    pub all: bool,
+
+    /// The branch to switch to
+    #[structopt(name = "BRANCH")]
+    pub branch: String,
+
+    /// Repositories to switch, each a clone URL, a path relative to the base directory, or an
+    /// owner/repo suffix that only one repository has
+    #[structopt(name = "REPO", raw(required_unless_one = r#"&["all", "GROUP"]"#))]
+    pub repos: Vec<String>,
+}
+
+#[derive(StructOpt)]
+pub enum GroupCommand {
+    /// Add repositories, patterns or other groups to a group, creating it if needed
+    #[structopt(name = "add")]
+    Add(GroupAddOptions),
+
+    /// Remove members from a group, or the whole group if no members are given
+    #[structopt(name = "remove")]
+    Remove(GroupRemoveOptions),
+
+    /// List every group and its members, or the repositories in one group
+    #[structopt(name = "list")]
+    List(GroupListOptions),
+}
+
+#[derive(StructOpt)]
+pub struct GroupAddOptions {
+    #[structopt(flatten)]
+    pub base: BaseOptions,
+
+    #[structopt(flatten)]
+    pub list: BaseListOptions,
+
+    /// Name of the group
+    #[structopt(name = "NAME")]
+    pub name: String,
+
+    /// Repositories, found the same way as for `where`, patterns like github.com/acme/* or names
+    /// of other groups
+    #[structopt(name = "MEMBER", raw(required = "true"))]
+    pub members: Vec<String>,
+}
+
+#[derive(StructOpt)]
+pub struct GroupRemoveOptions {
+    #[structopt(flatten)]
+    pub base: BaseOptions,
+
+    #[structopt(flatten)]
+    pub list: BaseListOptions,
+
+    /// Name of the group
+    #[structopt(name = "NAME")]
+    pub name: String,
+
+    /// Members to remove
+    #[structopt(name = "MEMBER")]
+    pub members: Vec<String>,
+}
+
+#[derive(StructOpt)]
+pub struct GroupListOptions {
+    #[structopt(flatten)]
+    pub base: BaseOptions,
+
+    #[structopt(flatten)]
+    pub list: BaseListOptions,
+
+    /// List the repositories in this group
+    #[structopt(name = "NAME")]
+    pub name: Option<String>,
+}
+
+#[derive(StructOpt)]
+pub struct StatsOptions {
+    #[structopt(flatten)]
+    pub base: BaseOptions,
+
+    #[structopt(flatten)]
+    pub list: BaseListOptions,
+}
+
+#[derive(StructOpt)]
+pub struct WhereOptions {
+    #[structopt(flatten)]
+    pub base: BaseOptions,
+
+    #[structopt(flatten)]
+    pub list: BaseListOptions,
+
+    /// Print every matching repository, best match first
+    #[structopt(short = "-a", long = "--all")]
+    pub all: bool,
+
+    /// A clone URL, owner/repo, or part of a repository's path
+    #[structopt(name = "QUERY")]
+    pub query: String,
+}
+
+#[derive(StructOpt)]
+pub struct ShellInitOptions {
+    /// The shell to print functions for
+    #[structopt(name = "SHELL", raw(possible_values = "&InitShell::variants()"))]
+    pub shell: InitShell,
+}
+
+#[derive(Clone, Copy)]
+pub enum InitShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl InitShell {
+    pub fn variants() -> [&'static str; 3] {
+        ["bash", "zsh", "fish"]
+    }
+}
+
+impl str::FromStr for InitShell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<InitShell, String> {
+        match s {
+            "bash" => Ok(InitShell::Bash),
+            "zsh" => Ok(InitShell::Zsh),
+            "fish" => Ok(InitShell::Fish),
+            _ => Err(format!("Unknown shell {}", s)),
+        }
+    }
+}
+
+#[derive(StructOpt)]
+pub struct DocsOptions {
+    /// Directory to write the man pages and markdown reference to
+    #[structopt(name = "OUT_DIR", parse(from_os_str))]
+    pub out_dir: path::PathBuf,
+}
+
+#[derive(StructOpt)]
+pub struct CompleteOptions {
+    #[structopt(flatten)]
+    pub base: BaseOptions,
+
+    /// What to complete, unless a command line follows `--`
+    #[structopt(
+        name = "KIND",
+        raw(
+            possible_values = "&CompletionKind::variants()",
+            required_unless = r#""LINE""#
+        )
+    )]
+    pub kind: Option<CompletionKind>,
+
+    /// Only print candidates starting with this prefix
+    #[structopt(name = "PREFIX", default_value = "")]
+    pub prefix: String,
+
+    /// The words of a command line after `git-project`, ending with the word
+    /// to complete
+    #[structopt(name = "LINE", raw(last = "true"))]
+    pub line: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompletionKind {
+    Repos,
+    Hosts,
+    Owners,
+    Remotes,
+}
+
+impl CompletionKind {
+    pub fn variants() -> [&'static str; 4] {
+        ["repos", "hosts", "owners", "remotes"]
+    }
+}
+
+impl str::FromStr for CompletionKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<CompletionKind, String> {
+        match s {
+            "repos" => Ok(CompletionKind::Repos),
+            "hosts" => Ok(CompletionKind::Hosts),
+            "owners" => Ok(CompletionKind::Owners),
+            "remotes" => Ok(CompletionKind::Remotes),
+            _ => Err(format!("Unknown completion kind {}", s)),
+        }
+    }
+}
+
 fn parse_canonical_path(path_str: &ffi::OsStr) -> Result<path::PathBuf, ffi::OsString> {
     path::Path::new(path_str).canonicalize().map_err(|err| {
         ffi::OsString::from(format!(
@@ -0,0 +1,113 @@
+use crate::{
+    config::Config,
+    err::{Err, Result},
+    options::{CheckOptions, Severity, WarningKind, WarningSetting},
+};
+use std::collections::HashMap;
+
+/// Which warnings `check` reports, and how severe each one is.
+///
+/// Every kind is reported at its default severity unless configured
+/// otherwise, except for kinds that must be turned on with `warn`.
+/// `gitproject.warn` and `gitproject.allow` entries are applied first, then
+/// `--warn` and `--allow`, so the command line always wins:
+///
+/// ```text
+/// [gitproject]
+///     warn = dirty=error,unpushed-tag=error
+///     allow = no-upstream
+/// ```
+///
+/// A repository can opt out of individual kinds with `gitproject.ignore` in
+/// its own config.
+pub struct WarningPolicy {
+    severities: HashMap<WarningKind, Severity>,
+}
+
+impl Default for WarningPolicy {
+    fn default() -> WarningPolicy {
+        WarningPolicy {
+            severities: WarningKind::variants()
+                .iter()
+                .map(|name| name.parse::<WarningKind>().unwrap())
+                .filter(|kind| kind.enabled_by_default())
+                .map(|kind| (kind, kind.default_severity()))
+                .collect(),
+        }
+    }
+}
+
+impl WarningPolicy {
+    pub fn new(
+        config: &Config,
+        warn: &[WarningSetting],
+        allow: &[WarningKind],
+    ) -> Result<WarningPolicy> {
+        let mut policy = WarningPolicy::default();
+
+        for setting in config_list(config, "warn")? {
+            policy.warn(setting);
+        }
+
+        for kind in config_list(config, "allow")? {
+            policy.severities.remove(&kind);
+        }
+
+        for setting in warn {
+            policy.warn(*setting);
+        }
+
+        for kind in allow {
+            policy.severities.remove(kind);
+        }
+
+        Ok(policy)
+    }
+
+    pub fn load(opts: &CheckOptions) -> Result<WarningPolicy> {
+        WarningPolicy::new(&Config::load()?, &opts.warn, &opts.allow)
+    }
+
+    fn warn(&mut self, setting: WarningSetting) {
+        let severity = setting
+            .severity
+            .unwrap_or_else(|| setting.kind.default_severity());
+
+        self.severities.insert(setting.kind, severity);
+    }
+
+    /// The severity to report a kind of warning with, or `None` if it should
+    /// not be reported
+    pub fn severity(&self, kind: WarningKind) -> Option<Severity> {
+        self.severities.get(&kind).cloned()
+    }
+}
+
+/// The warning kinds a repository ignores with `gitproject.ignore` in its own
+/// config
+pub fn repo_ignores(repo: &git2::Repository) -> Result<Vec<WarningKind>> {
+    let local = repo.config()?.open_level(git2::ConfigLevel::Local)?;
+
+    config_list(&Config::from_git(&local)?, "ignore")
+}
+
+/// Parse every comma separated item of every value of `key`
+fn config_list<T>(config: &Config, key: &str) -> Result<Vec<T>>
+where
+    T: std::str::FromStr<Err = String>,
+{
+    let mut items = Vec::new();
+
+    for value in config.get_all(None, key) {
+        for item in value.split(',').map(str::trim).filter(|i| !i.is_empty()) {
+            let parsed = item.parse().map_err(|message| Err::InvalidConfig {
+                key: format!("gitproject.{}", key),
+                message,
+            })?;
+
+            items.push(parsed);
+        }
+    }
+
+    Ok(items)
+}
@@ -0,0 +1,86 @@
+use crate::{config::Config, err::Result, options::RemoteOptions, util};
+
+const DEFAULT_REMOTE: &str = "origin";
+
+/// The ordered list of remote names that decide where a repository belongs.
+///
+/// Names given on the command line win. Otherwise the `gitproject.remote`
+/// setting for the remote's host is used, and finally "origin".
+pub struct RemotePreference {
+    names: Vec<String>,
+    config: Config,
+}
+
+/// A remote chosen to decide where a repository belongs
+pub struct CanonicalRemote {
+    pub name: String,
+    pub url: String,
+}
+
+impl RemotePreference {
+    pub fn new(opts: &RemoteOptions, config: Config) -> RemotePreference {
+        RemotePreference {
+            names: opts.remote.clone(),
+            config,
+        }
+    }
+
+    pub fn load(opts: &RemoteOptions) -> Result<RemotePreference> {
+        Ok(RemotePreference::new(opts, Config::load()?))
+    }
+
+    /// Preferred remote names for a host, most preferred first
+    pub fn for_host(&self, host: Option<&str>) -> Vec<&str> {
+        if !self.names.is_empty() {
+            return self.names.iter().map(String::as_str).collect();
+        }
+
+        let configured = self.config.host_values(host, "remote");
+
+        if configured.is_empty() {
+            vec![DEFAULT_REMOTE]
+        } else {
+            configured
+        }
+    }
+
+    /// The remote that decides where a repository belongs.
+    ///
+    /// Each remote is ranked by its position in the preference list for its
+    /// own host. If no remote is preferred, the first remote with a URL is
+    /// used.
+    pub fn canonical_remote(&self, repo: &git2::Repository) -> Result<Option<CanonicalRemote>> {
+        let mut candidates = Vec::new();
+
+        for name in repo.remotes()?.iter().flatten() {
+            if let Ok(remote) = repo.find_remote(name) {
+                if let Some(url) = remote.url() {
+                    candidates.push(CanonicalRemote {
+                        name: name.to_owned(),
+                        url: url.to_owned(),
+                    });
+                }
+            }
+        }
+
+        let preferred = candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, candidate)| {
+                let host = util::url_host(&candidate.url);
+                self.for_host(host.as_deref())
+                    .iter()
+                    .position(|name| *name == candidate.name)
+                    .map(|rank| (rank, i))
+            })
+            .min()
+            .map(|(_, i)| i)
+            .unwrap_or(0);
+
+        if candidates.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(candidates.swap_remove(preferred)))
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{
+    baseline::{Baseline, Snooze},
+    options::WarningKind,
+};
+use chrono::NaiveDate;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+
+const REPO: &str = "github.com/Kroger-Technology/git-project";
+const MESSAGE: &str = "local branch experiment has no upstream configured";
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn sample_baseline() -> Baseline {
+    Baseline::from_json(&json!({
+        "warnings": { REPO: [MESSAGE] },
+        "snoozed": [{ "repo": REPO, "kind": "dirty", "until": "2026-11-01" }],
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_baseline_suppresses_recorded_message() {
+    let baseline = sample_baseline();
+    let today = date("2026-10-01");
+
+    assert!(baseline.suppresses(REPO, WarningKind::NoUpstream, MESSAGE, today));
+    assert!(!baseline.suppresses(
+        REPO,
+        WarningKind::NoUpstream,
+        "local branch other has no upstream configured",
+        today
+    ));
+}
+
+#[test]
+fn test_baseline_snooze_ends() {
+    let baseline = sample_baseline();
+
+    assert!(baseline.suppresses(REPO, WarningKind::Dirty, "", date("2026-11-01")));
+    assert!(!baseline.suppresses(REPO, WarningKind::Dirty, "", date("2026-11-02")));
+    assert!(!baseline.suppresses("other", WarningKind::Dirty, "", date("2026-10-01")));
+}
+
+#[test]
+fn test_baseline_snooze_replaces_earlier_snooze() {
+    let mut baseline = sample_baseline();
+    baseline.snooze(Snooze {
+        repo: REPO.into(),
+        kind: WarningKind::Dirty,
+        until: date("2026-10-15"),
+    });
+
+    assert!(!baseline.suppresses(REPO, WarningKind::Dirty, "", date("2026-10-20")));
+}
+
+#[test]
+fn test_baseline_round_trip() {
+    let json = sample_baseline().to_json();
+    assert_eq!(Baseline::from_json(&json).unwrap().to_json(), json);
+}
+
+#[test]
+fn test_baseline_invalid_kind() {
+    let json = json!({ "snoozed": [{ "repo": REPO, "kind": "bogus", "until": "2026-11-01" }] });
+    assert!(Baseline::from_json(&json).is_err());
+}
+
+#[test]
+fn test_baseline_update_keeps_unchecked_repositories() {
+    let mut baseline = Baseline::from_json(&json!({
+        "warnings": {
+            "github.com/acme/api": ["working directory has changes not checked in"],
+            "github.com/acme/web": [MESSAGE],
+            "github.com/acme/old": [MESSAGE],
+        },
+        "snoozed": [],
+    }))
+    .unwrap();
+
+    let mut checked = BTreeMap::new();
+    checked.insert(
+        "github.com/acme/web".to_owned(),
+        BTreeSet::from(["local branch other has no upstream configured".to_owned()]),
+    );
+    checked.insert("github.com/acme/old".to_owned(), BTreeSet::new());
+    baseline.update_warnings(checked);
+
+    assert_eq!(
+        baseline.to_json()["warnings"],
+        json!({
+            "github.com/acme/api": ["working directory has changes not checked in"],
+            "github.com/acme/web": ["local branch other has no upstream configured"],
+        })
+    );
+}
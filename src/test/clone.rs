@@ -0,0 +1,62 @@
+use crate::{
+    commands::clone,
+    options::CloneOptions,
+    test::fixture::{self, TempDir},
+};
+use std::fs;
+use structopt::StructOpt;
+
+fn location(temp: &TempDir, url: &str) -> std::path::PathBuf {
+    let base_dir = temp.path().join("base");
+    let opts = CloneOptions::from_iter(vec![
+        "clone",
+        "-d",
+        base_dir.to_str().unwrap(),
+        "--remote",
+        "upstream",
+        url,
+    ]);
+
+    clone::location(&opts).unwrap()
+}
+
+#[test]
+fn test_location_of_fork() {
+    let temp = TempDir::new();
+    let base_dir = temp.path().join("base");
+
+    let repo = fixture::init_repo(&base_dir.join("github.com/acme/api"));
+    repo.remote("upstream", "https://github.com/acme/api.git")
+        .unwrap();
+    repo.remote("origin", "git@github.com:me/api.git").unwrap();
+
+    // A repository that cannot be opened is passed over
+    fs::create_dir_all(base_dir.join("github.com/broken/api/.git")).unwrap();
+
+    assert_eq!(
+        location(&temp, "https://github.com/me/api"),
+        base_dir.join("github.com/acme/api")
+    );
+    assert_eq!(
+        location(&temp, "https://github.com/other/api"),
+        base_dir.join("github.com/other/api")
+    );
+}
+
+#[test]
+fn test_location_other_host() {
+    let temp = TempDir::new();
+    let base_dir = temp.path().join("base");
+
+    // Only repositories under the URL's host are looked at
+    let repo = fixture::init_repo(&base_dir.join("gitlab.com/acme/api"));
+    repo.remote("upstream", "https://gitlab.com/acme/api.git")
+        .unwrap();
+    repo.remote("origin", "https://github.com/me/api.git")
+        .unwrap();
+
+    assert_eq!(
+        location(&temp, "https://github.com/me/api"),
+        base_dir.join("github.com/me/api")
+    );
+}
@@ -0,0 +1,164 @@
+use crate::{
+    commands::complete::{self, POSITIONAL_COMPLETIONS},
+    options::{CompletionKind, Options},
+    test::fixture::{self, TempDir},
+};
+use rstest::*;
+use structopt::{clap, StructOpt};
+
+/// A base directory with `github.com/acme/api`, which has the remotes
+/// `origin` and `upstream`, `github.com/acme/web` and `scratch`
+fn base_dir() -> TempDir {
+    let temp = TempDir::new();
+
+    let api = fixture::init_repo(&temp.path().join("github.com/acme/api"));
+    api.remote("origin", "git@github.com:me/api.git").unwrap();
+    api.remote("upstream", "git@github.com:acme/api.git")
+        .unwrap();
+
+    fixture::init_repo(&temp.path().join("github.com/acme/web"))
+        .remote("origin", "git@github.com:acme/web.git")
+        .unwrap();
+    fixture::init_repo(&temp.path().join("scratch"));
+
+    // Repositories inside repositories are not offered
+    fixture::init_repo(&temp.path().join("github.com/acme/api/vendor/lib"));
+
+    temp
+}
+
+#[rstest_parametrize(
+    prefix,
+    expected,
+    case("", "github.com/acme/api github.com/acme/web scratch"),
+    case("github.com/acme/a", "github.com/acme/api"),
+    case("s", "scratch"),
+    case("gitlab", "")
+)]
+fn test_complete_repos(prefix: &str, expected: &str) {
+    let temp = base_dir();
+    let candidates = complete::candidates(temp.path(), CompletionKind::Repos, prefix).unwrap();
+
+    assert_eq!(
+        candidates.into_iter().collect::<Vec<_>>().join(" "),
+        expected
+    );
+}
+
+#[rstest_parametrize(
+    prefix,
+    expected,
+    case("", "github.com"),
+    case("git", "github.com"),
+    case("s", "")
+)]
+fn test_complete_hosts(prefix: &str, expected: &str) {
+    let temp = base_dir();
+    let candidates = complete::candidates(temp.path(), CompletionKind::Hosts, prefix).unwrap();
+
+    assert_eq!(
+        candidates.into_iter().collect::<Vec<_>>().join(" "),
+        expected
+    );
+}
+
+#[rstest_parametrize(
+    prefix,
+    expected,
+    case("", "github.com/acme"),
+    case("github.com/a", "github.com/acme"),
+    case("github.com/z", "")
+)]
+fn test_complete_owners(prefix: &str, expected: &str) {
+    let temp = base_dir();
+    let candidates = complete::candidates(temp.path(), CompletionKind::Owners, prefix).unwrap();
+
+    assert_eq!(
+        candidates.into_iter().collect::<Vec<_>>().join(" "),
+        expected
+    );
+}
+
+#[rstest_parametrize(prefix, expected, case("", "origin upstream"), case("up", "upstream"))]
+fn test_complete_remotes(prefix: &str, expected: &str) {
+    let temp = base_dir();
+    let candidates = complete::candidates(temp.path(), CompletionKind::Remotes, prefix).unwrap();
+
+    assert_eq!(
+        candidates.into_iter().collect::<Vec<_>>().join(" "),
+        expected
+    );
+}
+
+#[rstest_parametrize(
+    line,
+    expected,
+    case("where ", "repos"),
+    case("rm -d /base ", "repos"),
+    case("mv github.com/acme/api ", "repos"),
+    case("switch ", ""),
+    case("switch feature ", "repos"),
+    case("switch --group work feature ", "repos"),
+    case("group add ", ""),
+    case("group add work ", "hosts owners repos"),
+    case("group remove work github.com ", "hosts owners repos"),
+    case("list --remote ", "remotes"),
+    case("where --base-dir ", ""),
+    case("where -", ""),
+    case("list ", ""),
+    case("", "")
+)]
+fn test_line_kinds(line: &str, expected: &str) {
+    let words: Vec<_> = line.split(' ').collect();
+    let (current, before) = words.split_last().unwrap();
+    let kinds: Vec<_> = complete::line_kinds(before, current)
+        .iter()
+        .map(|kind| format!("{:?}", kind).to_lowercase())
+        .collect();
+
+    assert_eq!(kinds.join(" "), expected);
+}
+
+#[rstest_parametrize(
+    line,
+    expected,
+    case("where github.com/acme/w", "github.com/acme/web"),
+    case(
+        "group add work g",
+        "github.com github.com/acme github.com/acme/api github.com/acme/web"
+    ),
+    case(
+        "group add work github.com/",
+        "github.com/acme github.com/acme/api github.com/acme/web"
+    ),
+    case("list --remote u", "upstream"),
+    case("list s", "")
+)]
+fn test_complete_line(line: &str, expected: &str) {
+    let temp = base_dir();
+    let words: Vec<_> = line.split(' ').map(String::from).collect();
+    let candidates = complete::line_candidates(temp.path(), &words).unwrap();
+
+    assert_eq!(
+        candidates.into_iter().collect::<Vec<_>>().join(" "),
+        expected
+    );
+}
+
+#[test]
+fn test_completed_subcommands_exist() {
+    for (subcommand, _, _) in POSITIONAL_COMPLETIONS {
+        let mut args = vec!["git-project"];
+        args.extend(subcommand.iter());
+        args.push("--help");
+
+        let err = Options::clap().get_matches_from_safe(args).err().unwrap();
+
+        assert_eq!(
+            err.kind,
+            clap::ErrorKind::HelpDisplayed,
+            "no subcommand {}",
+            subcommand.join(" ")
+        );
+    }
+}
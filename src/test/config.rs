@@ -0,0 +1,68 @@
+use crate::{config::Config, options::RemoteOptions, remotes::RemotePreference};
+
+fn sample_config() -> Config {
+    Config::from_entries(vec![
+        ("gitproject.remote", "origin"),
+        ("gitproject.github.com.remote", "upstream"),
+        ("gitproject.github.com.remote", "origin"),
+        ("gitproject.GitLab.Kroger.com.remote", "corp"),
+        ("core.remote", "ignored"),
+    ])
+}
+
+#[test]
+fn test_config_top_level_values() {
+    let config = sample_config();
+    assert_eq!(config.get_all(None, "remote"), vec!["origin"]);
+}
+
+#[test]
+fn test_config_host_values_in_order() {
+    let config = sample_config();
+    assert_eq!(
+        config.host_values(Some("github.com"), "remote"),
+        vec!["upstream", "origin"]
+    );
+}
+
+#[test]
+fn test_config_host_values_ignore_case() {
+    let config = sample_config();
+    assert_eq!(
+        config.host_values(Some("gitlab.kroger.com"), "remote"),
+        vec!["corp"]
+    );
+}
+
+#[test]
+fn test_config_host_values_fall_back_to_top_level() {
+    let config = sample_config();
+    assert_eq!(
+        config.host_values(Some("bitbucket.org"), "remote"),
+        vec!["origin"]
+    );
+}
+
+#[test]
+fn test_remote_preference_defaults_to_origin() {
+    let preference = RemotePreference::new(&RemoteOptions { remote: vec![] }, Config::default());
+    assert_eq!(preference.for_host(Some("github.com")), vec!["origin"]);
+}
+
+#[test]
+fn test_remote_preference_command_line_wins() {
+    let opts = RemoteOptions {
+        remote: vec!["fork".into()],
+    };
+    let preference = RemotePreference::new(&opts, sample_config());
+    assert_eq!(preference.for_host(Some("github.com")), vec!["fork"]);
+}
+
+#[test]
+fn test_remote_preference_per_host() {
+    let preference = RemotePreference::new(&RemoteOptions { remote: vec![] }, sample_config());
+    assert_eq!(
+        preference.for_host(Some("github.com")),
+        vec!["upstream", "origin"]
+    );
+}
@@ -0,0 +1,91 @@
+use crate::{
+    commands::duplicates::{self, Divergence},
+    config::Config,
+    options::RemoteOptions,
+    remotes::RemotePreference,
+    test::fixture::{self, TempDir},
+};
+use std::{path, process};
+
+/// A repository at the path of its `origin`, and a clone of it elsewhere
+/// whose `origin` is the ssh form of the same URL
+fn copies(temp: &TempDir) -> (path::PathBuf, path::PathBuf) {
+    let canonical = temp.path().join("github.com/acme/api");
+    let other = temp.path().join("old/api");
+
+    let repo = fixture::init_repo(&canonical);
+    repo.remote("origin", "https://github.com/acme/api.git")
+        .unwrap();
+
+    let status = process::Command::new("git")
+        .args(["clone", "-q"])
+        .arg(&canonical)
+        .arg(&other)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    git2::Repository::open(&other)
+        .unwrap()
+        .remote_set_url("origin", "git@github.com:acme/api.git")
+        .unwrap();
+
+    (canonical, other)
+}
+
+fn divergence(canonical: &path::Path, other: &path::Path) -> Divergence {
+    duplicates::divergence(
+        &duplicates::checkout(canonical).unwrap(),
+        &duplicates::checkout(other).unwrap(),
+    )
+}
+
+#[test]
+fn test_find_duplicates() {
+    let temp = TempDir::new();
+    let (canonical, other) = copies(&temp);
+    fixture::init_repo(&temp.path().join("github.com/acme/web"))
+        .remote("origin", "https://github.com/acme/web.git")
+        .unwrap();
+
+    let mut paths = vec![
+        other.clone(),
+        canonical.clone(),
+        temp.path().join("github.com/acme/web"),
+    ];
+    paths.sort();
+
+    let preference =
+        RemotePreference::new(&RemoteOptions { remote: Vec::new() }, Config::default());
+    let groups = duplicates::find_duplicates(&paths, temp.path(), &preference);
+
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].canonical_path, canonical);
+    assert_eq!(groups[0].url, "https://github.com/acme/api.git");
+    assert_eq!(groups[0].copies, vec![canonical, other]);
+}
+
+#[test]
+fn test_divergence() {
+    let temp = TempDir::new();
+    let (canonical, other) = copies(&temp);
+
+    assert_eq!(divergence(&canonical, &other), Divergence::Same);
+
+    fixture::commit(&git2::Repository::open(&canonical).unwrap(), "canonical");
+    assert_eq!(divergence(&canonical, &other), Divergence::Behind);
+    assert_eq!(divergence(&other, &canonical), Divergence::Ahead);
+
+    fixture::commit(&git2::Repository::open(&other).unwrap(), "other");
+    assert_eq!(divergence(&canonical, &other), Divergence::Diverged);
+}
+
+#[test]
+fn test_divergence_without_commits() {
+    let temp = TempDir::new();
+    let (canonical, _) = copies(&temp);
+    let empty = temp.path().join("empty");
+    git2::Repository::init(&empty).unwrap();
+
+    assert_eq!(divergence(&canonical, &empty), Divergence::Unknown);
+}
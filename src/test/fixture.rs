@@ -0,0 +1,86 @@
+use std::{
+    env, fs, path, process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A directory under the system temporary directory, removed when dropped
+pub struct TempDir {
+    path: path::PathBuf,
+}
+
+impl TempDir {
+    pub fn new() -> TempDir {
+        let name = format!(
+            "git-project-test-{}-{}",
+            std::process::id(),
+            NEXT_ID.fetch_add(1, Ordering::SeqCst)
+        );
+        let path = env::temp_dir().join(name);
+
+        fs::create_dir_all(&path).unwrap();
+
+        // Commands are given canonical base directories
+        TempDir {
+            path: path.canonicalize().unwrap(),
+        }
+    }
+
+    pub fn path(&self) -> &path::Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Create a repository with an identity configured and one empty commit on
+/// `master`
+pub fn init_repo(dir: &path::Path) -> git2::Repository {
+    let repo = git2::Repository::init(dir).unwrap();
+
+    {
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+    }
+
+    commit(&repo, "initial");
+    repo
+}
+
+/// Commit the current index on top of HEAD, if there is one
+pub fn commit(repo: &git2::Repository, message: &str) -> git2::Oid {
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    let tree = repo
+        .find_tree(repo.index().unwrap().write_tree().unwrap())
+        .unwrap();
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )
+    .unwrap()
+}
+
+/// Run git in a directory, failing the test if it fails
+pub fn git(dir: &path::Path, args: &[&str]) {
+    let status = process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {} failed", args.join(" "));
+}
@@ -0,0 +1,56 @@
+use crate::commands::gen_completions;
+use std::process::Command;
+use structopt::clap::Shell;
+
+/// Run the bash completion function on a command line, with `git-project`
+/// replaced by a function that offers the arguments it was given as its one
+/// candidate
+fn complete_bash(words: &str, cword: usize) -> String {
+    let script = format!(
+        "{}\n\
+         git-project() {{ printf '<%s>' \"$@\"; }}\n\
+         COMP_WORDS=({})\n\
+         COMP_CWORD={}\n\
+         _git_project_dynamic git-project\n\
+         printf '%s\\n' \"${{COMPREPLY[@]}}\"",
+        gen_completions::script(Shell::Bash),
+        words,
+        cword
+    );
+    let output = Command::new("bash").arg("-c").arg(script).output().unwrap();
+
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_bash_passes_command_line_to_complete() {
+    assert_eq!(
+        complete_bash("git-project switch -d /base feature ''", 5),
+        "<complete><--base-dir></base><--><switch><-d></base><feature><>\n"
+    );
+}
+
+#[test]
+fn test_bash_completes_group_members() {
+    assert_eq!(
+        complete_bash("git-project group add work git", 4),
+        "<complete><--><group><add><work><git>\n"
+    );
+}
+
+#[test]
+fn test_scripts_call_complete() {
+    for shell in &[Shell::Bash, Shell::Zsh, Shell::Fish] {
+        let script = gen_completions::script(*shell);
+
+        assert!(script.contains("git-project complete"), "{}", shell);
+    }
+}
+
+#[test]
+fn test_zsh_calls_clap_completion_once() {
+    let script = gen_completions::script(Shell::Zsh);
+
+    assert!(script.ends_with("_git_project_dynamic \"$@\"\n"));
+    assert_eq!(script.matches("\n_git-project \"$@\"").count(), 0);
+}
@@ -0,0 +1,135 @@
+use crate::commands::gen_docs;
+use structopt::clap::{App, Arg, SubCommand};
+
+fn app() -> App<'static, 'static> {
+    App::new("demo")
+        .version("1.0")
+        .about("Do things with -flags\n.and more")
+        .arg(
+            Arg::with_name("verbose")
+                .short("v")
+                .long("verbose")
+                .help("Talk more"),
+        )
+        .arg(Arg::with_name("NAME").required(true).help("Who to greet"))
+        .subcommand(
+            SubCommand::with_name("nested")
+                .about("A nested command")
+                .subcommand(SubCommand::with_name("deeper").about("Deeper still")),
+        )
+        .subcommand(SubCommand::with_name("hidden").setting(structopt::clap::AppSettings::Hidden))
+}
+
+fn render<F>(index: usize, write: F) -> String
+where
+    F: Fn(&mut Vec<u8>, &gen_docs::Page, usize),
+{
+    let pages = gen_docs::pages(&app(), "git-project-demo", "git project demo", "Demo").unwrap();
+    let (page, level) = &pages[index];
+    let mut out = Vec::new();
+    write(&mut out, page, *level);
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_pages() {
+    let pages = gen_docs::pages(&app(), "git-project-demo", "git project demo", "Demo").unwrap();
+    let mut out = Vec::new();
+
+    for (page, level) in &pages {
+        gen_docs::write_markdown_page(&mut out, page, *level).unwrap();
+    }
+
+    let headings: Vec<_> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .filter(|line| line.starts_with('#'))
+        .map(String::from)
+        .collect();
+
+    assert_eq!(
+        headings,
+        [
+            "# `git project demo`",
+            "## `git project demo nested`",
+            "### `git project demo nested deeper`",
+        ]
+    );
+}
+
+#[test]
+fn test_man_page() {
+    let man = render(1, |out, page, _| {
+        gen_docs::write_man_page(out, page).unwrap()
+    });
+
+    assert_eq!(
+        man,
+        format!(
+            r#".TH GIT\-PROJECT\-DEMO\-NESTED 1 "" "git-project {}" "Git Manual"
+.SH NAME
+git\-project\-demo\-nested \- A nested command
+.SH DESCRIPTION
+.nf
+demo\-nested
+A nested command
+
+USAGE:
+    demo <NAME> nested [SUBCOMMAND]
+
+FLAGS:
+    \-h, \-\-help       Prints help information
+    \-V, \-\-version    Prints version information
+
+SUBCOMMANDS:
+    deeper    Deeper still
+    help      Prints this message or the help of the given subcommand(s)
+.fi
+.SH COMMANDS
+.TP
+\fBgit\-project\-demo\-nested\-deeper\fR(1)
+Deeper still
+.SH SEE ALSO
+\fBgit\-project\-demo\fR(1)
+"#,
+            env!("CARGO_PKG_VERSION")
+        )
+    );
+}
+
+#[test]
+fn test_man_page_escapes_every_line() {
+    let man = render(0, |out, page, _| {
+        gen_docs::write_man_page(out, page).unwrap()
+    });
+
+    assert!(man.contains("\nDo things with \\-flags\n\\&.and more\n"));
+}
+
+#[test]
+fn test_markdown_page() {
+    let markdown = render(2, |out, page, level| {
+        gen_docs::write_markdown_page(out, page, level).unwrap()
+    });
+
+    assert_eq!(
+        markdown,
+        "### `git project demo nested deeper`
+
+Deeper still
+
+```
+demo-nested-deeper
+Deeper still
+
+USAGE:
+    demo nested deeper
+
+FLAGS:
+    -h, --help       Prints help information
+    -V, --version    Prints version information
+```
+
+"
+    );
+}
@@ -0,0 +1,101 @@
+use crate::{
+    commands::grep,
+    test::fixture::{self, TempDir},
+    util::PathFilter,
+};
+use regex::Regex;
+use std::{fs, path::Path};
+
+#[test]
+fn test_matching_lines() {
+    let regex = Regex::new("fo+").unwrap();
+    assert_eq!(
+        grep::matching_lines(&regex, b"foo\nbar\nfoo bar\n"),
+        vec![(1, "foo".to_owned()), (3, "foo bar".to_owned())]
+    );
+}
+
+#[test]
+fn test_matching_lines_skips_binary() {
+    let regex = Regex::new("foo").unwrap();
+    assert!(grep::matching_lines(&regex, b"foo\0bar").is_empty());
+}
+
+/// The matching lines of a search, with the path of the file they are in
+fn search(dir: &Path, rev: Option<&str>, pattern: &str) -> Vec<(String, usize, String)> {
+    let regex = Regex::new(pattern).unwrap();
+    let filter = PathFilter::new::<&str>(&[], "PATH").unwrap();
+
+    grep::search(dir, rev, &regex, &filter)
+        .unwrap()
+        .files
+        .into_iter()
+        .flat_map(|file| {
+            let path = file.path;
+            file.lines
+                .into_iter()
+                .map(move |(number, line)| (path.clone(), number, line))
+        })
+        .collect()
+}
+
+#[test]
+fn test_search_worktree() {
+    let temp = TempDir::new();
+    let dir = temp.path().join("repo");
+    let repo = fixture::init_repo(&dir);
+
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join("src/main.rs"), "fn main() {}\n// TODO: tests\n").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("src/main.rs")).unwrap();
+    index.write().unwrap();
+    fixture::commit(&repo, "main");
+
+    // The working tree is searched, not the committed file
+    fs::write(
+        dir.join("src/main.rs"),
+        "fn main() {}\n// TODO: tests\n// TODO: docs\n",
+    )
+    .unwrap();
+    // Untracked files are not searched
+    fs::write(dir.join("notes.txt"), "TODO: untracked\n").unwrap();
+
+    assert_eq!(
+        search(&dir, None, "TODO"),
+        vec![
+            ("src/main.rs".to_owned(), 2, "// TODO: tests".to_owned()),
+            ("src/main.rs".to_owned(), 3, "// TODO: docs".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_search_rev() {
+    let temp = TempDir::new();
+    let dir = temp.path().join("repo");
+    let repo = fixture::init_repo(&dir);
+
+    fs::write(dir.join("config.toml"), "version = 1\n").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("config.toml")).unwrap();
+    index.write().unwrap();
+    fixture::commit(&repo, "first");
+    fixture::git(&dir, &["tag", "v1"]);
+
+    fs::write(dir.join("config.toml"), "version = 2\n").unwrap();
+    index.add_path(Path::new("config.toml")).unwrap();
+    index.write().unwrap();
+    fixture::commit(&repo, "second");
+
+    assert_eq!(
+        search(&dir, Some("v1"), "version"),
+        vec![("config.toml".to_owned(), 1, "version = 1".to_owned())]
+    );
+    assert_eq!(
+        search(&dir, Some("HEAD"), "version"),
+        vec![("config.toml".to_owned(), 1, "version = 2".to_owned())]
+    );
+    // Repositories without the revision have nothing to search
+    assert!(search(&dir, Some("v2"), "version").is_empty());
+}
@@ -0,0 +1,125 @@
+use crate::{config::Config, groups, groups::Groups, test::fixture::TempDir};
+use rstest::*;
+use std::process;
+
+fn sample_groups() -> Groups {
+    Groups::new(&Config::from_entries(vec![
+        ("gitproject.group.payments", "github.com/acme/payments-*"),
+        ("gitproject.group.payments", "github.com/acme/ledger"),
+        ("gitproject.group.Checkout", "payments"),
+        ("gitproject.group.checkout", "github.com/acme/cart"),
+        ("gitproject.group.loop-a", "loop-b"),
+        ("gitproject.group.loop-b", "loop-a"),
+        ("gitproject.github.com.remote", "upstream"),
+    ]))
+}
+
+#[test]
+fn test_group_patterns() {
+    assert_eq!(
+        sample_groups().patterns(&["payments"]).unwrap(),
+        vec!["github.com/acme/payments-*", "github.com/acme/ledger"]
+    );
+}
+
+#[test]
+fn test_group_patterns_expand_nested_groups() {
+    assert_eq!(
+        sample_groups().patterns(&["CHECKOUT"]).unwrap(),
+        vec![
+            "github.com/acme/payments-*",
+            "github.com/acme/ledger",
+            "github.com/acme/cart"
+        ]
+    );
+}
+
+#[test]
+fn test_group_patterns_without_duplicates() {
+    assert_eq!(
+        sample_groups()
+            .patterns(&["payments", "checkout"])
+            .unwrap()
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn test_group_patterns_unknown_group() {
+    assert_eq!(
+        sample_groups()
+            .patterns(&["nothing"])
+            .unwrap_err()
+            .to_string(),
+        "Invalid group: no group named nothing"
+    );
+}
+
+#[test]
+fn test_group_patterns_cycle() {
+    assert!(sample_groups().patterns(&["loop-a"]).is_err());
+}
+
+#[test]
+fn test_group_members_only_from_group_subsection() {
+    assert_eq!(
+        sample_groups()
+            .iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>(),
+        vec!["checkout", "loop-a", "loop-b", "payments"]
+    );
+}
+
+#[rstest_parametrize(
+    name,
+    valid,
+    case("payments", true),
+    case("team-2", true),
+    case("2fast", false),
+    case("with space", false),
+    case("dotted.name", false),
+    case("", false)
+)]
+fn test_group_name_valid(name: &str, valid: bool) {
+    assert_eq!(groups::is_valid_name(name), valid);
+}
+
+#[rstest_parametrize(
+    value,
+    case("github.com/acme/api"),
+    case("github.com/acme/payments-*"),
+    case("github.com/**/a?c"),
+    case("weird/[name]+(1)^$|{2}\\")
+)]
+fn test_value_regex_unsets_only_exact_value(value: &str) {
+    let temp = TempDir::new();
+    let file = temp.path().join("config");
+    let git_config = |args: &[&str]| {
+        process::Command::new("git")
+            .args(["config", "--file"])
+            .arg(&file)
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    for member in &[value, "github.com/acme/api-extra", "other"] {
+        git_config(&["--add", "gitproject.group.test", member]);
+    }
+
+    assert!(git_config(&[
+        "--unset-all",
+        "gitproject.group.test",
+        &groups::value_regex(value)
+    ])
+    .status
+    .success());
+
+    let left = git_config(&["--get-all", "gitproject.group.test"]);
+    assert_eq!(
+        String::from_utf8_lossy(&left.stdout),
+        "github.com/acme/api-extra\nother\n"
+    );
+}
@@ -0,0 +1,193 @@
+use crate::{
+    commands::list,
+    info::RepoInfo,
+    options::{ListOptions, SortKey},
+    test::fixture::TempDir,
+};
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use rstest::*;
+use serde_json::{json, Value};
+use std::path;
+use structopt::StructOpt;
+
+fn headers() -> Vec<String> {
+    vec!["path".into(), "branch".into(), "dirty".into()]
+}
+
+fn rows() -> Vec<Vec<Value>> {
+    vec![
+        vec![json!("github.com/acme/api"), json!("main"), json!(false)],
+        vec![json!("scratch, \"old\""), Value::Null, json!(true)],
+    ]
+}
+
+#[rstest_parametrize(
+    field,
+    escaped,
+    case("plain", "plain"),
+    case("a,b", "\"a,b\""),
+    case("say \"hi\"", "\"say \"\"hi\"\"\""),
+    case("two\nlines", "\"two\nlines\"")
+)]
+fn test_csv_escape(field: &str, escaped: &str) {
+    assert_eq!(list::csv_escape(field), escaped);
+}
+
+#[rstest_parametrize(
+    field,
+    escaped,
+    case("plain", "plain"),
+    case("a\tb", "a b"),
+    case("two\r\nlines", "two  lines"),
+    case("a,\"b\"", "a,\"b\"")
+)]
+fn test_tsv_escape(field: &str, escaped: &str) {
+    assert_eq!(list::tsv_escape(field), escaped);
+}
+
+#[test]
+fn test_write_csv() {
+    let mut out = Vec::new();
+    list::write_delimited(&mut out, &headers(), &rows(), ',', '\n', list::csv_escape).unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "path,branch,dirty\n\
+         github.com/acme/api,main,false\n\
+         \"scratch, \"\"old\"\"\",,true\n"
+    );
+}
+
+#[test]
+fn test_write_tsv_null_terminated() {
+    let mut out = Vec::new();
+    list::write_delimited(&mut out, &headers(), &rows(), '\t', '\0', list::tsv_escape).unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "path\tbranch\tdirty\0\
+         github.com/acme/api\tmain\tfalse\0\
+         scratch, \"old\"\t\ttrue\0"
+    );
+}
+
+#[test]
+fn test_write_json() {
+    let mut out = Vec::new();
+    list::write_json(&mut out, &headers(), rows()).unwrap();
+
+    let written: Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(
+        written,
+        json!([
+            {"path": "github.com/acme/api", "branch": "main", "dirty": false},
+            {"path": "scratch, \"old\"", "branch": null, "dirty": true},
+        ])
+    );
+
+    // Columns keep the order they were asked for in
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.find("\"path\"").unwrap() < text.find("\"branch\"").unwrap());
+    assert!(text.find("\"branch\"").unwrap() < text.find("\"dirty\"").unwrap());
+}
+
+/// A date this many days ago
+fn days_ago(days: i64) -> Option<DateTime<FixedOffset>> {
+    Some((Utc::now() - Duration::days(days)).into())
+}
+
+fn info(
+    path: &str,
+    last_commit: Option<DateTime<FixedOffset>>,
+    last_modified: Option<DateTime<FixedOffset>>,
+    size: Option<u64>,
+    remote_url: Option<&str>,
+) -> RepoInfo {
+    RepoInfo {
+        path: path::PathBuf::from(path),
+        branch: None,
+        upstream: None,
+        ahead: None,
+        behind: None,
+        dirty: None,
+        remote_url: remote_url.map(String::from),
+        last_commit,
+        author: None,
+        last_modified,
+        size,
+    }
+}
+
+/// `old` was committed to long ago but modified recently, `new` was committed
+/// to recently, and `unborn` has neither
+fn infos() -> Vec<RepoInfo> {
+    vec![
+        info(
+            "old",
+            days_ago(100),
+            days_ago(1),
+            Some(10),
+            Some("https://gitlab.com/acme/old.git"),
+        ),
+        info("unborn", None, None, None, None),
+        info(
+            "new",
+            days_ago(2),
+            days_ago(50),
+            Some(300),
+            Some("git@github.com:acme/new.git"),
+        ),
+        info(
+            "another",
+            days_ago(30),
+            None,
+            Some(20),
+            Some("https://github.com/acme/another.git"),
+        ),
+    ]
+}
+
+fn paths(infos: &[RepoInfo]) -> String {
+    infos
+        .iter()
+        .map(|info| info.path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[rstest_parametrize(
+    key,
+    expected,
+    case("path", "another new old unborn"),
+    case("last-commit", "new another old unborn"),
+    case("last-modified", "old new unborn another"),
+    case("size", "new another old unborn"),
+    case("host", "another new old unborn")
+)]
+fn test_sort(key: &str, expected: &str) {
+    let mut infos = infos();
+    list::sort(key.parse::<SortKey>().unwrap(), &mut infos);
+
+    assert_eq!(paths(&infos), expected);
+}
+
+#[rstest_parametrize(
+    args,
+    expected,
+    case("--since 1w", "old new"),
+    case("--since 2mo", "old new another"),
+    case("--stale 1w", "unborn another"),
+    case("--stale 2mo", "unborn"),
+    case("--since 2mo --stale 1w", "another")
+)]
+fn test_filter_recency(args: &str, expected: &str) {
+    let temp = TempDir::new();
+    let base_dir = temp.path().to_str().unwrap();
+    let mut argv = vec!["list", "-d", base_dir];
+    argv.extend(args.split(' '));
+
+    let mut infos = infos();
+    list::filter_recency(&ListOptions::from_iter(argv), &mut infos);
+
+    assert_eq!(paths(&infos), expected);
+}
@@ -0,0 +1,97 @@
+use crate::{
+    commands::lookup::{self, match_score},
+    options::BaseListOptions,
+    test::fixture::{self, TempDir},
+};
+use rstest::*;
+use std::path::{self, Path};
+
+const PATH: &str = "github.com/Kroger-Technology/git-project";
+
+#[rstest_parametrize(
+    query,
+    score,
+    case("Kroger-Technology/git-project", 4),
+    case("git-project", 3),
+    case("GIT-PROJECT", 3),
+    case("project", 2),
+    case("kroger", 1),
+    case("gtprj", 0)
+)]
+fn test_match_score(query: &str, score: u8) {
+    assert_eq!(match_score(Path::new(PATH), query), Some(score));
+}
+
+#[rstest_parametrize(query, case("gitlab"), case(""), case("/"))]
+fn test_match_score_no_match(query: &str) {
+    assert_eq!(match_score(Path::new(PATH), query), None);
+}
+
+/// A base directory holding `github.com/acme/api`, `github.com/acme/web` and
+/// `gitlab.com/acme/api`, next to a repository outside of it
+fn base_with_repositories() -> (TempDir, path::PathBuf) {
+    let temp = TempDir::new();
+    let base_dir = temp.path().join("base");
+
+    for repo in &[
+        "github.com/acme/api",
+        "github.com/acme/web",
+        "gitlab.com/acme/api",
+    ] {
+        fixture::init_repo(&base_dir.join(repo));
+    }
+
+    fixture::init_repo(&temp.path().join("outside"));
+
+    (temp, base_dir)
+}
+
+fn list_options() -> BaseListOptions {
+    BaseListOptions {
+        deep_recurse: false,
+        groups: Vec::new(),
+    }
+}
+
+#[rstest_parametrize(
+    repo,
+    expected,
+    case("github.com/acme/web", "github.com/acme/web"),
+    case("https://github.com/acme/api.git", "github.com/acme/api"),
+    case("git@gitlab.com:acme/api.git", "gitlab.com/acme/api"),
+    case("acme/web", "github.com/acme/web"),
+    case("github.com/acme/../acme/api", "github.com/acme/api")
+)]
+fn test_resolve_repository(repo: &str, expected: &str) {
+    let (_temp, base_dir) = base_with_repositories();
+
+    assert_eq!(
+        lookup::resolve_repository(&base_dir, repo, &list_options()).unwrap(),
+        base_dir.join(expected)
+    );
+}
+
+#[rstest_parametrize(
+    repo,
+    case("acme/api"),
+    case("web"),
+    case("github.com/acme"),
+    case("."),
+    case("../outside"),
+    case("git@github.com:../../outside")
+)]
+fn test_resolve_repository_rejects(repo: &str) {
+    let (_temp, base_dir) = base_with_repositories();
+
+    assert!(lookup::resolve_repository(&base_dir, repo, &list_options()).is_err());
+}
+
+#[test]
+fn test_resolve_repository_rejects_absolute_path_outside_base() {
+    let (temp, base_dir) = base_with_repositories();
+    let outside = temp.path().join("outside");
+
+    assert!(
+        lookup::resolve_repository(&base_dir, &outside.to_string_lossy(), &list_options()).is_err()
+    );
+}
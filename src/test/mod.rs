@@ -1 +1,24 @@
+mod baseline;
+mod check;
+mod clone;
+mod complete;
+mod config;
+mod duplicates;
+mod fixture;
+mod gen_completions;
+mod gen_docs;
+mod grep;
+mod groups;
+mod list;
+mod lookup;
+mod mv;
+mod organize;
+mod policy;
+mod prune_branches;
+mod remote_rewrite;
+mod rm;
+mod standup;
+mod stats;
+mod switch;
+mod tree;
 mod util;
@@ -0,0 +1,92 @@
+use crate::{
+    commands::mv,
+    test::fixture::{self, TempDir},
+};
+use std::{fs, path, process};
+
+fn git(dir: &path::Path, args: &[&str]) {
+    let status = process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::null())
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+fn read(file: &path::Path) -> String {
+    fs::read_to_string(file).unwrap().trim_end().to_owned()
+}
+
+#[test]
+fn test_relink() {
+    let temp = TempDir::new();
+    let file = temp.path().join("link");
+    let from = temp.path().join("from");
+    let to = temp.path().join("to");
+
+    fs::write(
+        &file,
+        format!("gitdir: {}\n", from.join(".git/worktrees/wt").display()),
+    )
+    .unwrap();
+
+    assert!(mv::relink(&file, &from, &to, "gitdir: ").unwrap());
+    assert_eq!(
+        read(&file),
+        format!("gitdir: {}", to.join(".git/worktrees/wt").display())
+    );
+
+    // Links to anywhere else are left alone
+    assert!(!mv::relink(&file, &from, &temp.path().join("other"), "gitdir: ").unwrap());
+    assert_eq!(
+        read(&file),
+        format!("gitdir: {}", to.join(".git/worktrees/wt").display())
+    );
+}
+
+#[test]
+fn test_repair_worktrees_of_moved_repository() {
+    let temp = TempDir::new();
+    let from = temp.path().join("from");
+    let to = temp.path().join("to");
+    let worktree = temp.path().join("worktree");
+
+    fixture::init_repo(&from);
+    git(&from, &["worktree", "add", "-q", "-b", "wt", "../worktree"]);
+
+    fs::rename(&from, &to).unwrap();
+
+    assert_eq!(
+        mv::repair_worktrees(&from, &to).unwrap(),
+        vec![worktree.join(".git")]
+    );
+    assert_eq!(
+        read(&worktree.join(".git")),
+        format!("gitdir: {}", to.join(".git/worktrees/worktree").display())
+    );
+    git(&worktree, &["status"]);
+}
+
+#[test]
+fn test_repair_worktrees_of_moved_worktree() {
+    let temp = TempDir::new();
+    let main = temp.path().join("main");
+    let from = temp.path().join("from");
+    let to = temp.path().join("to");
+
+    fixture::init_repo(&main);
+    git(&main, &["worktree", "add", "-q", "-b", "wt", "../from"]);
+
+    fs::rename(&from, &to).unwrap();
+
+    let gitdir = main.join(".git/worktrees/from/gitdir");
+
+    assert_eq!(
+        mv::repair_worktrees(&from, &to).unwrap(),
+        vec![gitdir.clone()]
+    );
+    assert_eq!(read(&gitdir), to.join(".git").display().to_string());
+    git(&to, &["status"]);
+}
@@ -0,0 +1,126 @@
+use crate::{
+    commands::organize::{self, Move},
+    options::{OrganizeOptions, RemoteOptions},
+    test::fixture::{self, TempDir},
+};
+use rstest::*;
+use std::{fs, path};
+
+fn options(temp: &TempDir) -> OrganizeOptions {
+    OrganizeOptions {
+        dir: temp.path().join("old"),
+        new_dir: temp.path().join("new"),
+        dry_run: false,
+        interactive: false,
+        remote: RemoteOptions { remote: Vec::new() },
+    }
+}
+
+fn planned(opts: &OrganizeOptions, from: &str, to: &str) -> Move {
+    Move {
+        from: opts.dir.join(from),
+        to: opts.new_dir.join(to),
+        remote: Some("origin".into()),
+        warnings: Vec::new(),
+    }
+}
+
+/// The destinations of a plan, relative to NEW_DIR
+fn destinations(opts: &OrganizeOptions, plan: &[Move]) -> Vec<String> {
+    let mut destinations: Vec<_> = plan
+        .iter()
+        .map(|m| {
+            m.to.strip_prefix(&opts.new_dir)
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    destinations.sort();
+    destinations
+}
+
+#[rstest_parametrize(
+    input,
+    expected,
+    case("y\ny\n", "github.com/acme/api github.com/acme/web"),
+    case("yes\nn\n", "github.com/acme/api"),
+    case("maybe\nn\ny\n", "github.com/acme/web"),
+    case("e\nmine/api\ny\ny\n", "github.com/acme/web mine/api"),
+    case("e\n\ny\ny\n", "github.com/acme/api github.com/acme/web"),
+    case(
+        "e\n../api\ne\n/tmp/api\ne\n./api\ny\ny\n",
+        "github.com/acme/api github.com/acme/web"
+    ),
+    case("y\nq\n", "quit"),
+    // Input ending early quits, like `q`
+    case("y\n", "quit")
+)]
+fn test_review(input: &str, expected: &str) {
+    let temp = TempDir::new();
+    let opts = options(&temp);
+    let plan = vec![
+        planned(&opts, "api", "github.com/acme/api"),
+        planned(&opts, "web", "github.com/acme/web"),
+    ];
+
+    let approved = organize::review(&opts, plan, &mut input.as_bytes()).unwrap();
+
+    match approved {
+        Some(approved) => assert_eq!(destinations(&opts, &approved).join(" "), expected),
+        None => assert_eq!(expected, "quit"),
+    }
+}
+
+#[test]
+fn test_plan_moves() {
+    let temp = TempDir::new();
+    let opts = options(&temp);
+
+    fixture::init_repo(&opts.dir.join("api"))
+        .remote("origin", "https://github.com/acme/api.git")
+        .unwrap();
+    fixture::init_repo(&opts.dir.join("scratch/notes"));
+    fixture::init_repo(&opts.dir.join("scratch/todo"));
+
+    let plan = organize::plan_moves(&opts).unwrap();
+
+    // Repositories without a remote keep their place under no-remote
+    assert_eq!(
+        destinations(&opts, &plan),
+        [
+            "github.com/acme/api",
+            "no-remote/scratch/notes",
+            "no-remote/scratch/todo",
+        ]
+    );
+    assert!(organize::conflicts(&opts, &plan).is_empty());
+}
+
+#[test]
+fn test_conflicts() {
+    let temp = TempDir::new();
+    let opts = options(&temp);
+    fs::create_dir_all(opts.new_dir.join("github.com/acme/web")).unwrap();
+
+    let plan = vec![
+        planned(&opts, "api", "github.com/acme/api"),
+        planned(&opts, "api-copy", "github.com/acme/api"),
+        planned(&opts, "web", "github.com/acme/web"),
+        planned(&opts, "other", "github.com/acme/other"),
+    ];
+
+    assert_eq!(
+        organize::conflicts(&opts, &plan),
+        [
+            format!(
+                "api and api-copy would both move to {}",
+                path::Path::new("github.com/acme/api").display()
+            ),
+            format!(
+                "web cannot move to {}, which already exists",
+                path::Path::new("github.com/acme/web").display()
+            ),
+        ]
+    );
+}
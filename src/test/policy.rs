@@ -0,0 +1,84 @@
+use crate::{
+    config::Config,
+    options::{Severity, WarningKind, WarningSetting},
+    policy::WarningPolicy,
+};
+use rstest::*;
+
+#[rstest_parametrize(
+    setting,
+    kind,
+    case("dirty", "dirty"),
+    case("unpushed-tag=error", "unpushed-tag"),
+    case("path = info", "path")
+)]
+fn test_warning_setting_kind(setting: &str, kind: &str) {
+    let setting: WarningSetting = setting.parse().unwrap();
+    assert_eq!(setting.kind.to_string(), kind);
+}
+
+#[test]
+fn test_warning_setting_severity() {
+    let setting: WarningSetting = "ahead=error".parse().unwrap();
+    assert_eq!(setting.severity, Some(Severity::Error));
+}
+
+#[rstest_parametrize(setting, case("bogus"), case("dirty=fatal"))]
+fn test_invalid_warning_setting(setting: &str) {
+    assert!(setting.parse::<WarningSetting>().is_err());
+}
+
+#[test]
+fn test_policy_defaults() {
+    let policy = WarningPolicy::default();
+    assert_eq!(policy.severity(WarningKind::Dirty), Some(Severity::Warn));
+    assert_eq!(policy.severity(WarningKind::Path), Some(Severity::Info));
+}
+
+#[test]
+fn test_policy_from_config() {
+    let config = Config::from_entries(vec![
+        ("gitproject.warn", "dirty=error, stash=info"),
+        ("gitproject.allow", "path"),
+    ]);
+    let policy = WarningPolicy::new(&config, &[], &[]).unwrap();
+
+    assert_eq!(policy.severity(WarningKind::Dirty), Some(Severity::Error));
+    assert_eq!(policy.severity(WarningKind::Stash), Some(Severity::Info));
+    assert_eq!(policy.severity(WarningKind::Path), None);
+}
+
+#[test]
+fn test_policy_command_line_wins() {
+    let config = Config::from_entries(vec![
+        ("gitproject.warn", "dirty=error"),
+        ("gitproject.allow", "path"),
+    ]);
+    let warn = ["path".parse().unwrap()];
+    let allow = [WarningKind::Dirty];
+    let policy = WarningPolicy::new(&config, &warn, &allow).unwrap();
+
+    assert_eq!(policy.severity(WarningKind::Dirty), None);
+    assert_eq!(policy.severity(WarningKind::Path), Some(Severity::Info));
+}
+
+#[test]
+fn test_policy_invalid_config() {
+    let config = Config::from_entries(vec![("gitproject.allow", "dirty,bogus")]);
+    assert!(WarningPolicy::new(&config, &[], &[]).is_err());
+}
+
+#[test]
+fn test_policy_duplicate_needs_warn() {
+    assert_eq!(
+        WarningPolicy::default().severity(WarningKind::Duplicate),
+        None
+    );
+
+    let warn = ["duplicate".parse().unwrap()];
+    let policy = WarningPolicy::new(&Config::default(), &warn, &[]).unwrap();
+    assert_eq!(
+        policy.severity(WarningKind::Duplicate),
+        Some(Severity::Warn)
+    );
+}
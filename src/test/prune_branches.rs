@@ -0,0 +1,108 @@
+use crate::{
+    commands::prune_branches::{self, Reason},
+    test::fixture::{self, TempDir},
+};
+
+/// Classify a local branch against `origin/master`
+fn classify(repo: &git2::Repository, name: &str) -> Option<Reason> {
+    let default_tip = repo.refname_to_id("refs/remotes/origin/master").unwrap();
+    let branch = repo.find_branch(name, git2::BranchType::Local).unwrap();
+
+    prune_branches::classify(repo, &repo.config().unwrap(), &branch, name, default_tip).unwrap()
+}
+
+fn set_upstream(repo: &git2::Repository, branch: &str, upstream: &str) {
+    let mut config = repo.config().unwrap();
+    config
+        .set_str(&format!("branch.{}.remote", branch), "origin")
+        .unwrap();
+    config
+        .set_str(
+            &format!("branch.{}.merge", branch),
+            &format!("refs/heads/{}", upstream),
+        )
+        .unwrap();
+}
+
+/// A repository whose `origin/master` is two commits ahead of `merged`, with
+/// `fresh` at `origin/master` and `work` one commit past it
+fn repository(dir: &std::path::Path) -> git2::Repository {
+    let repo = fixture::init_repo(dir);
+
+    let merged = fixture::commit(&repo, "merged work");
+    fixture::commit(&repo, "more work");
+    let default = fixture::commit(&repo, "latest");
+    let work = fixture::commit(&repo, "unmerged work");
+
+    for (name, oid) in &[("merged", merged), ("fresh", default), ("work", work)] {
+        repo.branch(name, &repo.find_commit(*oid).unwrap(), false)
+            .unwrap();
+    }
+
+    repo.reference("refs/remotes/origin/master", default, false, "fetch")
+        .unwrap();
+    repo.remote("origin", "https://example.com/acme/repo.git")
+        .unwrap();
+
+    repo
+}
+
+#[test]
+fn test_classify_merged() {
+    let temp = TempDir::new();
+    let repo = repository(temp.path());
+
+    assert_eq!(classify(&repo, "merged"), Some(Reason::Merged));
+}
+
+#[test]
+fn test_classify_branch_at_default_tip() {
+    let temp = TempDir::new();
+    let repo = repository(temp.path());
+
+    assert_eq!(classify(&repo, "fresh"), None);
+
+    // Kept while its upstream exists
+    let fresh = repo.refname_to_id("refs/heads/fresh").unwrap();
+    repo.reference("refs/remotes/origin/fresh", fresh, false, "fetch")
+        .unwrap();
+    set_upstream(&repo, "fresh", "fresh");
+    assert_eq!(classify(&repo, "fresh"), None);
+
+    repo.find_reference("refs/remotes/origin/fresh")
+        .unwrap()
+        .delete()
+        .unwrap();
+    assert_eq!(
+        classify(&repo, "fresh"),
+        Some(Reason::UpstreamGone("origin/fresh".into()))
+    );
+}
+
+#[test]
+fn test_classify_unmerged() {
+    let temp = TempDir::new();
+    let repo = repository(temp.path());
+
+    // Without an upstream there is nothing to tell whether it was pushed
+    assert_eq!(classify(&repo, "work"), None);
+
+    set_upstream(&repo, "work", "work");
+    assert_eq!(
+        classify(&repo, "work"),
+        Some(Reason::Unpushed("origin/work".into(), 1))
+    );
+
+    let work = repo.refname_to_id("refs/heads/work").unwrap();
+    repo.reference("refs/remotes/origin/pushed", work, false, "fetch")
+        .unwrap();
+    assert_eq!(
+        classify(&repo, "work"),
+        Some(Reason::UpstreamGone("origin/work".into()))
+    );
+
+    // A branch whose upstream still exists is kept
+    repo.reference("refs/remotes/origin/work", work, false, "fetch")
+        .unwrap();
+    assert_eq!(classify(&repo, "work"), None);
+}
@@ -0,0 +1,111 @@
+use crate::{commands::remote_rewrite::UrlRewrite, options::Protocol};
+use rstest::*;
+
+#[rstest_parametrize(
+    url,
+    expected,
+    case(
+        "https://gitlab.old.com/team/repo.git",
+        "https://gitlab.new.com/team/repo.git"
+    ),
+    case("https://gitlab.old.com/repo", "https://gitlab.new.com/repo")
+)]
+fn test_rewrite_prefix(url: &str, expected: &str) {
+    let rewrite = UrlRewrite::new(
+        Some("https://gitlab.old.com/"),
+        Some("https://gitlab.new.com/"),
+        false,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(rewrite.apply(url).unwrap().unwrap(), expected);
+}
+
+#[rstest_parametrize(
+    url,
+    case("https://github.com/team/repo.git"),
+    case("git@gitlab.old.com:team/repo.git")
+)]
+fn test_rewrite_prefix_no_match(url: &str) {
+    let rewrite = UrlRewrite::new(
+        Some("https://gitlab.old.com/"),
+        Some("https://gitlab.new.com/"),
+        false,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(rewrite.apply(url).unwrap(), None);
+}
+
+#[test]
+fn test_rewrite_regex() {
+    let rewrite = UrlRewrite::new(
+        Some(r"^git@gitlab\.old\.com:(\w+)/"),
+        Some("git@gitlab.new.com:$1/"),
+        true,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(
+        rewrite
+            .apply("git@gitlab.old.com:team/repo.git")
+            .unwrap()
+            .unwrap(),
+        "git@gitlab.new.com:team/repo.git"
+    );
+}
+
+#[test]
+fn test_rewrite_invalid_regex() {
+    assert!(UrlRewrite::new(Some("(unclosed"), Some(""), true, None).is_err());
+}
+
+#[test]
+fn test_rewrite_protocol_only() {
+    let rewrite = UrlRewrite::new(None, None, false, Some(Protocol::Ssh)).unwrap();
+
+    assert_eq!(
+        rewrite
+            .apply("https://github.com/team/repo.git")
+            .unwrap()
+            .unwrap(),
+        "git@github.com:team/repo.git"
+    );
+    assert_eq!(rewrite.apply("git@github.com:team/repo.git").unwrap(), None);
+}
+
+#[test]
+fn test_rewrite_prefix_and_protocol() {
+    let rewrite = UrlRewrite::new(
+        Some("git@gitlab.old.com:"),
+        Some("git@gitlab.new.com:"),
+        false,
+        Some(Protocol::Https),
+    )
+    .unwrap();
+
+    assert_eq!(
+        rewrite
+            .apply("git@gitlab.old.com:team/repo.git")
+            .unwrap()
+            .unwrap(),
+        "https://gitlab.new.com/team/repo.git"
+    );
+}
+
+#[test]
+fn test_rewrite_unconvertible_url_is_an_error() {
+    let rewrite = UrlRewrite::new(
+        Some("/srv/git/"),
+        Some("/mnt/git/"),
+        false,
+        Some(Protocol::Ssh),
+    )
+    .unwrap();
+
+    assert!(rewrite.apply("/srv/git/repo.git").is_err());
+    assert_eq!(rewrite.apply("git@github.com:team/repo.git").unwrap(), None);
+}
@@ -0,0 +1,156 @@
+use crate::{
+    commands::{check::Warning, rm},
+    err::Err,
+    options::RmOptions,
+    test::fixture::{self, TempDir},
+};
+use std::{fs, path, process};
+use structopt::StructOpt;
+
+#[test]
+fn test_unpushed_commits() {
+    let temp = TempDir::new();
+    let repo = fixture::init_repo(temp.path());
+    let pushed = repo.head().unwrap().target().unwrap();
+
+    repo.reference("refs/remotes/origin/master", pushed, false, "push")
+        .unwrap();
+    assert_eq!(rm::unpushed_commits(&repo).unwrap(), 0);
+
+    fixture::commit(&repo, "second");
+    let unpushed = fixture::commit(&repo, "third");
+    assert_eq!(rm::unpushed_commits(&repo).unwrap(), 2);
+
+    // Commits held only by a tag or another branch count as well
+    let tagged = fixture::commit(&repo, "tagged");
+    repo.reference("refs/tags/v1.0", tagged, false, "tag")
+        .unwrap();
+    repo.reference("refs/heads/master", unpushed, true, "reset")
+        .unwrap();
+    assert_eq!(rm::unpushed_commits(&repo).unwrap(), 3);
+
+    repo.reference("refs/remotes/origin/master", tagged, true, "push")
+        .unwrap();
+    assert_eq!(rm::unpushed_commits(&repo).unwrap(), 0);
+}
+
+#[test]
+fn test_remove_empty_parents() {
+    let temp = TempDir::new();
+    let base_dir = temp.path().join("base");
+
+    fs::create_dir_all(base_dir.join("github.com/acme")).unwrap();
+    fs::create_dir_all(base_dir.join("gitlab.com/acme/api")).unwrap();
+    fs::create_dir_all(base_dir.join("gitlab.com/other/api")).unwrap();
+
+    rm::remove_empty_parents(&base_dir.join("github.com/acme/api"), &base_dir);
+    assert!(!base_dir.join("github.com").exists());
+    assert!(base_dir.exists());
+
+    fs::remove_dir(base_dir.join("gitlab.com/acme/api")).unwrap();
+    rm::remove_empty_parents(&base_dir.join("gitlab.com/acme/api"), &base_dir);
+    assert!(!base_dir.join("gitlab.com/acme").exists());
+    assert!(base_dir.join("gitlab.com/other/api").exists());
+}
+
+/// A repository under `base/example.com/acme/api` with its master branch
+/// pushed to `remote.git` and a lightweight tag of the pushed commit
+fn pushed_repo_with_tag(temp: &TempDir) -> path::PathBuf {
+    let remote = temp.path().join("remote.git");
+    git2::Repository::init_bare(&remote).unwrap();
+
+    let dir = temp.path().join("base/example.com/acme/api");
+    let repo = fixture::init_repo(&dir);
+    repo.remote("origin", remote.to_str().unwrap()).unwrap();
+    fixture::git(&dir, &["push", "-q", "-u", "origin", "master"]);
+    fixture::git(&dir, &["tag", "v1.0"]);
+
+    dir
+}
+
+fn rm_options(temp: &TempDir, args: &[&str]) -> RmOptions {
+    let base_dir = temp.path().join("base");
+    let mut all = vec!["rm", "-d", base_dir.to_str().unwrap()];
+    all.extend(args);
+    all.push("acme/api");
+
+    RmOptions::from_iter(all)
+}
+
+fn bundle_heads(bundle: &path::Path) -> String {
+    let output = process::Command::new("git")
+        .args(["bundle", "list-heads"])
+        .arg(bundle)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_rm_unreachable_remote() {
+    let temp = TempDir::new();
+    let dir = pushed_repo_with_tag(&temp);
+    fs::remove_dir_all(temp.path().join("remote.git")).unwrap();
+
+    match rm::run(&rm_options(&temp, &[])) {
+        Err(Err::UnverifiedTags { path, remotes }) => {
+            assert_eq!(path, path::Path::new("example.com/acme/api"));
+            assert_eq!(remotes, vec!["origin"]);
+        }
+        other => panic!("expected UnverifiedTags, got {:?}", other.err()),
+    }
+    assert!(dir.exists());
+
+    // Forced, every tag is saved since none could be checked
+    let bundle = temp.path().join("api.bundle");
+    rm::run(&rm_options(
+        &temp,
+        &["--force", "--bundle", bundle.to_str().unwrap()],
+    ))
+    .unwrap();
+    assert!(!dir.exists());
+    assert!(bundle_heads(&bundle).contains("refs/tags/v1.0"));
+}
+
+#[test]
+fn test_rm_bundles_tag_of_pushed_commit() {
+    let temp = TempDir::new();
+    let dir = pushed_repo_with_tag(&temp);
+    let bundle = temp.path().join("api.bundle");
+
+    rm::run(&rm_options(
+        &temp,
+        &["--force", "--bundle", bundle.to_str().unwrap()],
+    ))
+    .unwrap();
+
+    assert!(!dir.exists());
+    assert!(bundle_heads(&bundle).contains("refs/tags/v1.0"));
+}
+
+#[test]
+fn test_unpushed_tags() {
+    let temp = TempDir::new();
+    let dir = pushed_repo_with_tag(&temp);
+    let repo = git2::Repository::open(&dir).unwrap();
+    fixture::git(&dir, &["tag", "v0.9"]);
+
+    let unpushed = vec![Warning::UnpushedTag {
+        tag: "v1.0".into(),
+        target: "0000000".into(),
+    }];
+    assert_eq!(
+        rm::unpushed_tags(&repo, &unpushed).unwrap(),
+        vec!["refs/tags/v1.0"]
+    );
+
+    let unknown = vec![Warning::TagsUnknown {
+        remote: "origin".into(),
+    }];
+    assert_eq!(
+        rm::unpushed_tags(&repo, &unknown).unwrap(),
+        vec!["refs/tags/v0.9", "refs/tags/v1.0"]
+    );
+}
@@ -0,0 +1,33 @@
+use crate::commands::standup::Author;
+use rstest::*;
+
+#[rstest_parametrize(
+    pattern,
+    matches,
+    case("me@example.com", true),
+    case("ME@EXAMPLE.COM", true),
+    case("jane", true),
+    case("example.com", true),
+    case("someone@example.com", false)
+)]
+fn test_author_pattern_matches(pattern: &str, matches: bool) {
+    assert_eq!(
+        Author::Pattern(pattern.into()).matches("Jane Doe", "me@example.com"),
+        matches
+    );
+}
+
+#[rstest_parametrize(
+    email,
+    matches,
+    case("bob@corp.com", true),
+    case("Bob@Corp.com", true),
+    case("jimbob@corp.com", false),
+    case("bob@corp.com.au", false)
+)]
+fn test_author_email_matches(email: &str, matches: bool) {
+    assert_eq!(
+        Author::Email("bob@corp.com".into()).matches("Bob", email),
+        matches
+    );
+}
@@ -0,0 +1,107 @@
+use crate::{
+    commands::stats,
+    test::fixture::{self, TempDir},
+};
+use rstest::*;
+use std::{fs, path, process};
+
+#[rstest_parametrize(
+    path,
+    host,
+    owner,
+    case("github.com/acme/api", "github.com", "github.com/acme"),
+    case("github.com/acme/api/vendor/lib", "github.com", "github.com/acme"),
+    case("gitlab.com/project", "gitlab.com", "gitlab.com"),
+    case("scratch", ".", ".")
+)]
+fn test_host_and_owner(path: &str, host: &str, owner: &str) {
+    assert_eq!(
+        stats::host_and_owner(path::Path::new(path)),
+        (host.to_owned(), owner.to_owned())
+    );
+}
+
+#[test]
+fn test_measure() {
+    let temp = TempDir::new();
+    let dir = &temp.path().join("repo");
+    let repo = fixture::init_repo(dir);
+
+    write(dir, ".gitignore", "target/\n*.log\n");
+    write(dir, "src/main.rs", "fn main() {}\n");
+    add(&repo, &[".gitignore", "src/main.rs"]);
+    fixture::commit(&repo, "add sources");
+
+    let status = process::Command::new("git")
+        .args(["gc", "-q"])
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    // One more commit leaves a blob, a tree and a commit loose
+    write(dir, "README", "readme\n");
+    add(&repo, &["README"]);
+    fixture::commit(&repo, "add readme");
+
+    write(dir, "notes.txt", "untracked\n");
+    write(dir, "build.log", "ignored\n");
+    write(dir, "target/debug/app", "0123456789");
+
+    // Nested repositories are measured on their own with --deep-recurse,
+    // even in an ignored directory
+    fixture::init_repo(&dir.join("target/checkout"));
+    write(dir, "target/checkout/big", &"x".repeat(1000));
+    fixture::init_repo(&dir.join("vendor/lib"));
+    write(dir, "vendor/lib/big", &"x".repeat(1000));
+
+    // Like a submodule, this one's .git is a file pointing elsewhere, and it
+    // is never measured on its own
+    fixture::git(
+        temp.path(),
+        &["init", "-q", "--separate-git-dir", "sub.git", "repo/sub"],
+    );
+    write(dir, "sub/file", "sub\n");
+
+    let worktree = "target/\n*.log\n".len() as u64
+        + "fn main() {}\n".len() as u64
+        + "readme\n".len() as u64
+        + "untracked\n".len() as u64
+        + "sub\n".len() as u64;
+    let ignored = "ignored\n".len() as u64 + 10;
+
+    let deep = stats::measure(dir, true).unwrap();
+
+    assert_eq!(deep.worktree, worktree);
+    assert_eq!(deep.ignored, ignored);
+    assert_eq!(deep.packs, 1);
+    assert_eq!(deep.loose_objects, 3);
+    assert!(deep.git > 0);
+    assert_eq!(deep.total(), deep.git + deep.worktree + deep.ignored);
+
+    // Without it, they are part of the repository they are in
+    let shallow = stats::measure(dir, false).unwrap();
+
+    assert_eq!(shallow.worktree, worktree + 1000);
+    assert!(shallow.ignored > ignored + 1000);
+    assert!(shallow.git > deep.git);
+    assert_eq!(shallow.packs, 1);
+    // The commit and empty tree of vendor/lib
+    assert_eq!(shallow.loose_objects, 5);
+}
+
+fn write(dir: &path::Path, file: &str, content: &str) {
+    let path = dir.join(file);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn add(repo: &git2::Repository, files: &[&str]) {
+    let mut index = repo.index().unwrap();
+
+    for file in files {
+        index.add_path(path::Path::new(file)).unwrap();
+    }
+
+    index.write().unwrap();
+}
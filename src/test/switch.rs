@@ -0,0 +1,132 @@
+use crate::{
+    commands::switch::{self, Outcome},
+    options::{BaseListOptions, BaseOptions, RemoteOptions, SwitchOptions},
+    remotes::RemotePreference,
+    test::fixture::{self, TempDir},
+};
+use std::{fs, path};
+
+/// A repository on `master` with a tracked `README`, a local branch
+/// `feature`, and a remote branch `origin/shared` one commit ahead
+fn repository(dir: &path::Path) -> git2::Repository {
+    let repo = fixture::init_repo(dir);
+
+    fs::write(dir.join("README"), "readme\n").unwrap();
+    {
+        let mut index = repo.index().unwrap();
+        index.add_path(path::Path::new("README")).unwrap();
+        index.write().unwrap();
+    }
+    let master = fixture::commit(&repo, "add readme");
+
+    repo.branch("feature", &repo.find_commit(master).unwrap(), false)
+        .unwrap();
+
+    let shared = fixture::commit(&repo, "shared work");
+    repo.reference("refs/remotes/origin/shared", shared, false, "fetch")
+        .unwrap();
+    repo.reference("refs/heads/master", master, true, "reset")
+        .unwrap();
+    repo.remote("origin", "https://example.com/acme/repo.git")
+        .unwrap();
+
+    repo
+}
+
+fn options(branch: &str, create: bool) -> SwitchOptions {
+    SwitchOptions {
+        base: BaseOptions {
+            base_dir: path::PathBuf::new(),
+        },
+        list: BaseListOptions {
+            deep_recurse: false,
+            groups: Vec::new(),
+        },
+        remote: RemoteOptions { remote: Vec::new() },
+        create,
+        from_default: false,
+        all: false,
+        branch: branch.into(),
+        repos: Vec::new(),
+    }
+}
+
+fn switch(dir: &path::Path, branch: &str, create: bool) -> Outcome {
+    let preference =
+        RemotePreference::new(&RemoteOptions { remote: Vec::new() }, Default::default());
+
+    switch::switch(dir, &options(branch, create), &preference).unwrap()
+}
+
+fn head(repo: &git2::Repository) -> String {
+    repo.head().unwrap().name().unwrap().to_owned()
+}
+
+#[test]
+fn test_switch_existing_branch() {
+    let temp = TempDir::new();
+    let repo = repository(temp.path());
+
+    assert_eq!(switch(temp.path(), "feature", false), Outcome::Switched);
+    assert_eq!(head(&repo), "refs/heads/feature");
+    assert_eq!(switch(temp.path(), "feature", false), Outcome::AlreadyOn);
+    assert_eq!(switch(temp.path(), "feature", true), Outcome::Exists);
+    assert_eq!(switch(temp.path(), "missing", false), Outcome::Missing);
+}
+
+#[test]
+fn test_switch_creates_branch() {
+    let temp = TempDir::new();
+    let repo = repository(temp.path());
+
+    assert_eq!(
+        switch(temp.path(), "new", true),
+        Outcome::Created("HEAD".into())
+    );
+    assert_eq!(head(&repo), "refs/heads/new");
+}
+
+#[test]
+fn test_switch_refuses_dirty_repository() {
+    let temp = TempDir::new();
+    let repo = repository(temp.path());
+
+    fs::write(temp.path().join("README"), "changed\n").unwrap();
+
+    assert_eq!(switch(temp.path(), "feature", false), Outcome::Dirty);
+    assert_eq!(head(&repo), "refs/heads/master");
+}
+
+#[test]
+fn test_switch_removes_branch_when_checkout_fails() {
+    let temp = TempDir::new();
+    let repo = repository(temp.path());
+
+    // Another git process holding the index stops the checkout
+    fs::write(temp.path().join(".git/index.lock"), "").unwrap();
+
+    let preference =
+        RemotePreference::new(&RemoteOptions { remote: Vec::new() }, Default::default());
+    assert!(switch::switch(temp.path(), &options("shared", false), &preference).is_err());
+
+    assert_eq!(head(&repo), "refs/heads/master");
+    assert!(repo.find_branch("shared", git2::BranchType::Local).is_err());
+}
+
+#[test]
+fn test_switch_tracks_remote_branch() {
+    let temp = TempDir::new();
+    let repo = repository(temp.path());
+
+    assert_eq!(
+        switch(temp.path(), "shared", false),
+        Outcome::Tracking("origin/shared".into())
+    );
+    assert_eq!(head(&repo), "refs/heads/shared");
+
+    let branch = repo.find_branch("shared", git2::BranchType::Local).unwrap();
+    assert_eq!(
+        branch.upstream().unwrap().name().unwrap(),
+        Some("origin/shared")
+    );
+}
@@ -0,0 +1,36 @@
+use crate::tree::Tree;
+use std::path::Path;
+
+fn sample_tree() -> Tree {
+    let mut tree = Tree::default();
+    tree.insert(Path::new("github.com/org/one"), "*".into());
+    tree.insert(Path::new("github.com/org/two"), String::new());
+    tree.insert(Path::new("github.com/me/three"), "↑2".into());
+    tree.insert(Path::new("gitlab.com/x/y"), String::new());
+    tree
+}
+
+#[test]
+fn test_tree_render() {
+    assert_eq!(
+        sample_tree().render(None),
+        vec![
+            "github.com (3)",
+            "├── me (1)",
+            "│   └── three ↑2",
+            "└── org (2)",
+            "    ├── one *",
+            "    └── two",
+            "gitlab.com (1)",
+            "└── x (1)",
+            "    └── y",
+        ]
+    );
+}
+
+#[test]
+fn test_tree_render_width() {
+    let lines = sample_tree().render(Some(8));
+    assert_eq!(lines[0], "github.…");
+    assert_eq!(lines[5], "    └──…");
+}
@@ -0,0 +1,80 @@
+use std::{collections::BTreeMap, path};
+
+/// Repositories arranged by their path components, such as host and owner
+#[derive(Default)]
+pub struct Tree {
+    children: BTreeMap<String, Tree>,
+    /// Set if a repository lives at this node, holding the text shown after
+    /// its name
+    repo: Option<String>,
+    count: usize,
+}
+
+impl Tree {
+    pub fn insert(&mut self, repo_path: &path::Path, label: String) {
+        let mut node = self;
+        node.count += 1;
+
+        for component in repo_path.components() {
+            let name = component.as_os_str().to_string_lossy().into_owned();
+            node = node.children.entry(name).or_default();
+            node.count += 1;
+        }
+
+        node.repo = Some(label);
+    }
+
+    /// Render the tree as lines, each cut to `width` characters if given
+    pub fn render(&self, width: Option<usize>) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for (name, child) in &self.children {
+            child.render_node(name, "", "", &mut lines);
+        }
+
+        if let Some(width) = width {
+            for line in &mut lines {
+                if line.chars().count() > width {
+                    *line = line.chars().take(width.saturating_sub(1)).collect();
+                    line.push('…');
+                }
+            }
+        }
+
+        lines
+    }
+
+    fn render_node(&self, name: &str, prefix: &str, child_prefix: &str, lines: &mut Vec<String>) {
+        let mut line = format!("{}{}", prefix, name);
+
+        if let Some(label) = &self.repo {
+            if !label.is_empty() {
+                line.push(' ');
+                line.push_str(label);
+            }
+        }
+
+        if !self.children.is_empty() {
+            line.push_str(&format!(" ({})", self.count));
+        }
+
+        lines.push(line);
+
+        let last = self.children.len().saturating_sub(1);
+
+        for (i, (name, child)) in self.children.iter().enumerate() {
+            let (branch, indent) = if i == last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+
+            child.render_node(
+                name,
+                &format!("{}{}", child_prefix, branch),
+                &format!("{}{}", child_prefix, indent),
+                lines,
+            );
+        }
+    }
+}